@@ -0,0 +1,20 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use eddsa_poseidon::{derive_public_key, sign_message, verify_signature};
+use std::hint::black_box;
+
+fn bench_signature(c: &mut Criterion) {
+    let private_key = b"bench private key";
+    let message = [2u8; 32];
+    let public_key = derive_public_key(private_key).unwrap();
+    let signature = sign_message(private_key, &message).unwrap();
+
+    c.bench_function("sign_message", |b| {
+        b.iter(|| sign_message(black_box(private_key), black_box(&message)).unwrap())
+    });
+    c.bench_function("verify_signature", |b| {
+        b.iter(|| verify_signature(black_box(&message), black_box(&signature), black_box(&public_key)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_signature);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+use crate::{derive_public_key, sign_message, verify_signature};
+use std::time::{Duration, Instant};
+
+const PRIVATE_KEY: &[u8] = b"bench private key";
+const MESSAGE: &[u8] = &[2u8; 32];
+
+/// Measures the total time needed to sign `iterations` messages.
+pub fn bench_sign(iterations: usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        sign_message(PRIVATE_KEY, MESSAGE).unwrap();
+    }
+    start.elapsed()
+}
+
+/// Measures the total time needed to verify `iterations` signatures.
+/// Signing and key derivation happen once, outside of the measured loop.
+pub fn bench_verify(iterations: usize) -> Duration {
+    let public_key = derive_public_key(PRIVATE_KEY).unwrap();
+    let signature = sign_message(PRIVATE_KEY, MESSAGE).unwrap();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        assert!(verify_signature(MESSAGE, &signature, &public_key).unwrap());
+    }
+    start.elapsed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_smoke() {
+        assert!(bench_sign(1) > Duration::ZERO);
+        assert!(bench_verify(1) > Duration::ZERO);
+    }
+}
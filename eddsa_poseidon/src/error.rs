@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// Errors returned by the Semaphore identity and EdDSA helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SemaphoreError {
+    /// A parameter is missing or does not have the expected type.
+    InvalidParameter(String),
+    /// The private key could not be decoded or used to derive an identity.
+    InvalidPrivateKey(String),
+    /// The message is not a valid field element.
+    InvalidMessage(String),
+    /// The public key is not a valid Baby Jubjub point.
+    InvalidPublicKey(String),
+    /// A derived commitment does not match the expected one.
+    CommitmentMismatch(String),
+    /// The public key is the curve identity, i.e. the secret scalar is zero.
+    IdentityPublicKey,
+    /// The Poseidon hasher could not be built or rejected its inputs.
+    Poseidon(String),
+    /// The signature is malformed or out of range.
+    InvalidSignature(String),
+    /// An exported identity uses a format version this library does not know.
+    UnsupportedExportVersion(u8),
+    /// A byte buffer does not have the required length.
+    InvalidLength { expected: usize, actual: usize },
+    /// A point does not decode to a valid point of the prime-order subgroup.
+    InvalidPoint(String),
+    /// A scalar is outside its allowed range.
+    InvalidScalar(String),
+    /// The private key is trivially guessable, e.g. empty or all zero bytes.
+    WeakKey,
+    /// A BIP-39 mnemonic has unknown words, a bad word count or a bad checksum.
+    InvalidMnemonic(String),
+}
+
+impl fmt::Display for SemaphoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SemaphoreError::InvalidParameter(msg) => write!(f, "Invalid parameter: {}", msg),
+            SemaphoreError::InvalidPrivateKey(msg) => write!(f, "Invalid private key: {}", msg),
+            SemaphoreError::InvalidMessage(msg) => write!(f, "Invalid message: {}", msg),
+            SemaphoreError::InvalidPublicKey(msg) => write!(f, "Invalid public key: {}", msg),
+            SemaphoreError::CommitmentMismatch(msg) => write!(f, "Commitment mismatch: {}", msg),
+            SemaphoreError::IdentityPublicKey => write!(f, "Public key is the identity point"),
+            SemaphoreError::Poseidon(msg) => write!(f, "Poseidon error: {}", msg),
+            SemaphoreError::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
+            SemaphoreError::UnsupportedExportVersion(v) => write!(f, "Unsupported export version: {:#04x}", v),
+            SemaphoreError::InvalidLength { expected, actual } => {
+                write!(f, "Invalid length: expected {} bytes, got {}", expected, actual)
+            }
+            SemaphoreError::InvalidPoint(msg) => write!(f, "Invalid point: {}", msg),
+            SemaphoreError::InvalidScalar(msg) => write!(f, "Invalid scalar: {}", msg),
+            SemaphoreError::WeakKey => write!(f, "Private key is empty or all zeros"),
+            SemaphoreError::InvalidMnemonic(msg) => write!(f, "Invalid mnemonic: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SemaphoreError {}
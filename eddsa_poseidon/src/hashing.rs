@@ -0,0 +1,179 @@
+use crate::error::SemaphoreError;
+use ark_bn254::Fr as Fra;
+use ark_ff::{BigInteger, PrimeField};
+use baby_jubjub::R;
+use light_poseidon::{Poseidon, PoseidonHasher};
+use num_bigint::{BigInt, Sign};
+use utils::scalar::reduce;
+
+/// Converts a BigInt into an arkworks BN254 scalar through its little-endian bytes.
+/// Values outside `[0, R)` (including negative ones) are reduced modulo `R`.
+pub fn bigint_to_fr(value: &BigInt) -> Fra {
+    let (_, bytes) = if value.sign() == Sign::Minus || value >= &*R {
+        reduce(value, &R).to_bytes_le()
+    } else {
+        value.to_bytes_le()
+    };
+    Fra::from_le_bytes_mod_order(&bytes)
+}
+
+/// Converts an arkworks BN254 scalar back into a BigInt.
+pub fn fr_to_bigint(value: &Fra) -> BigInt {
+    BigInt::from_bytes_le(Sign::Plus, &value.into_bigint().to_bytes_le())
+}
+
+/// Hashes field elements with the circom-compatible Poseidon instance of the given arity.
+/// Fails if the arity is unsupported or does not match the number of inputs.
+pub fn poseidon_with_arity(arity: usize, inputs: &[BigInt]) -> Result<BigInt, SemaphoreError> {
+    let mut poseidon = Poseidon::<Fra>::new_circom(arity).map_err(|e| SemaphoreError::Poseidon(e.to_string()))?;
+    let inputs: Vec<Fra> = inputs.iter().map(bigint_to_fr).collect();
+    let hash = poseidon.hash(&inputs).map_err(|e| SemaphoreError::Poseidon(e.to_string()))?;
+    Ok(fr_to_bigint(&hash))
+}
+
+/// Hashes field elements with the circom-compatible Poseidon instance of matching arity.
+pub fn poseidon(inputs: &[BigInt]) -> Result<BigInt, SemaphoreError> {
+    poseidon_with_arity(inputs.len(), inputs)
+}
+
+/// A reusable two-input Poseidon instance, for hashing in loops without rebuilding the
+/// circom parameters on every call.
+pub struct PoseidonHasher2 {
+    poseidon: Poseidon<Fra>,
+}
+
+impl PoseidonHasher2 {
+    pub fn new() -> Self {
+        Self {
+            poseidon: Poseidon::<Fra>::new_circom(2).expect("Poseidon supports two inputs"),
+        }
+    }
+
+    /// Hashes two field elements, reducing them modulo `R` first.
+    pub fn hash(&mut self, a: &BigInt, b: &BigInt) -> BigInt {
+        let hash = self
+            .poseidon
+            .hash(&[bigint_to_fr(a), bigint_to_fr(b)])
+            .expect("Poseidon accepts two inputs");
+        fr_to_bigint(&hash)
+    }
+}
+
+impl Default for PoseidonHasher2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for PoseidonHasher2 {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for PoseidonHasher2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PoseidonHasher2")
+    }
+}
+
+/// Number of input bytes absorbed per sponge step; 31 bytes always fit below `R`.
+const SPONGE_CHUNK_BYTES: usize = 31;
+
+/// Hashes an arbitrary-length byte string to a field element with a Poseidon sponge.
+///
+/// Padding: a single `0x01` byte is appended and the result is zero-padded to a multiple of
+/// 31 bytes, so the empty input still absorbs one chunk and inputs differing only in trailing
+/// zeros hash differently. Each chunk is read as a little-endian integer and absorbed as
+/// `state = poseidon([state, chunk])`, starting from `state = 0`; the final state is squeezed.
+pub fn poseidon_sponge(input: &[u8]) -> BigInt {
+    let mut padded = input.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().div_ceil(SPONGE_CHUNK_BYTES) * SPONGE_CHUNK_BYTES, 0);
+
+    padded
+        .chunks(SPONGE_CHUNK_BYTES)
+        .fold(BigInt::from(0), |state, chunk| {
+            let element = BigInt::from_bytes_le(Sign::Plus, chunk);
+            poseidon(&[state, element]).expect("Poseidon supports two inputs")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    fn string_path(value: &BigInt) -> Fra {
+        let n: BigUint = value.to_string().parse().unwrap();
+        Fra::from(n)
+    }
+
+    #[test]
+    fn test_bigint_to_fr_matches_string_path() {
+        let values = [
+            BigInt::from(0),
+            BigInt::from(1),
+            BigInt::from(2),
+            BigInt::from(u64::MAX),
+            BigInt::from(1) << 200u32,
+            &*R - 1,
+            &*R + 5,
+        ];
+        for value in values.iter() {
+            assert_eq!(bigint_to_fr(value), string_path(value));
+            assert_eq!(fr_to_bigint(&bigint_to_fr(value)), reduce(value, &R));
+        }
+    }
+
+    #[test]
+    fn test_poseidon_errors_instead_of_panicking() {
+        let too_many: Vec<BigInt> = (0..13).map(BigInt::from).collect();
+        assert!(matches!(poseidon(&too_many), Err(SemaphoreError::Poseidon(_))));
+        assert!(matches!(poseidon(&[]), Err(SemaphoreError::Poseidon(_))));
+        assert!(matches!(
+            poseidon_with_arity(2, &[BigInt::from(1), BigInt::from(2), BigInt::from(3)]),
+            Err(SemaphoreError::Poseidon(_))
+        ));
+        assert!(poseidon(&[BigInt::from(1), BigInt::from(2)]).is_ok());
+    }
+
+    #[test]
+    fn test_poseidon_hasher2_matches_poseidon() {
+        let mut hasher = PoseidonHasher2::new();
+        for (a, b) in [(0, 0), (1, 2), (2, 1), (123456789, 987654321)] {
+            let (a, b) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(hasher.hash(&a, &b), poseidon(&[a.clone(), b.clone()]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_poseidon_sponge_vectors() {
+        let empty = poseidon_sponge(&[]);
+        assert_eq!(empty, poseidon(&[BigInt::from(0), BigInt::from(1)]).unwrap());
+        assert_eq!(
+            empty.to_string(),
+            "12583541437132735734108669866114103169564651237895298778035846191048104863326"
+        );
+        assert_eq!(
+            poseidon_sponge(&[7u8; 31]).to_string(),
+            "8964915463650910523209717822580343585541362507562663517397843639559089842107"
+        );
+        let long: Vec<u8> = (0..100).collect();
+        assert_eq!(
+            poseidon_sponge(&long).to_string(),
+            "3355720297934100079848680175139294967060130727895614040565934549836620384204"
+        );
+    }
+
+    #[test]
+    fn test_poseidon_sponge_padding_separates_trailing_zeros() {
+        assert_ne!(poseidon_sponge(&[]), poseidon_sponge(&[0]));
+        assert_ne!(poseidon_sponge(&[1]), poseidon_sponge(&[1, 0]));
+    }
+
+    #[test]
+    fn test_bigint_to_fr_negative() {
+        assert_eq!(bigint_to_fr(&BigInt::from(-1)), string_path(&(&*R - 1)));
+    }
+}
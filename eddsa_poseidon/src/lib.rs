@@ -1,105 +1,493 @@
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod error;
+pub mod hashing;
+#[cfg(feature = "sss")]
+pub mod sss;
+pub mod stream;
 pub mod util_functions;
-use util_functions::{Signature, prune_buffer, hash_input};
-use baby_jubjub::{BASE8, Fr, Point, add_point, in_curve, mul_point_escalar, pack_point, SUBORDER, unpack_point};
+pub mod verifier;
+use error::SemaphoreError;
+#[cfg(feature = "sss")]
+pub use sss::{recombine_private_key, split_private_key};
+use hashing::{poseidon, poseidon_with_arity};
+use util_functions::{Signature, clamp_scalar_bytes, hash_input};
+use baby_jubjub::{BASE8, Fr, Fs, ORDER, Point, R, add_point, in_curve, in_subgroup, is_identity, mul_point_escalar, pack_point, SUBORDER, unpack_point};
 use utils::conversions::{le_bigint_to_bytes, le_bytes_to_bigint};
 use ::utils::scalar::{shift_right, mul};
-use light_poseidon::{Poseidon, PoseidonHasher};
 use std::error::Error;
-use num_bigint::{BigInt, BigUint};
-use ark_bn254::Fr as Fra;
+use num_bigint::BigInt;
 
 /// Supported hashing algorithm (only BLAKE1 in this version).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SupportedHashingAlgorithms {
     Blake1,
 }
 
-fn string_to_biguint(num_str: &str) -> BigUint {
+pub fn poseidon5(nodes: Vec<String>) -> Result<BigInt, SemaphoreError> {
+    let inputs = nodes
+        .iter()
+        .map(|s| string_to_bigint(s))
+        .collect::<Result<Vec<BigInt>, SemaphoreError>>()?;
+    poseidon_with_arity(5, &inputs)
+}
+
+fn string_to_bigint(num_str: &str) -> Result<BigInt, SemaphoreError> {
     num_str
         .parse()
-        .expect("Failed to parse the string into BigUint")
+        .map_err(|_| SemaphoreError::InvalidParameter(format!("'{}' is not a decimal integer", num_str)))
 }
 
-pub fn poseidon5(nodes: Vec<String>) -> String {
-    let mut poseidon = Poseidon::<Fra>::new_circom(5).unwrap();
-
-    let inputs: Vec<Fra> = nodes
-        .iter()
-        .map(|s| ark_bn254::Fr::from(string_to_biguint(s)))
-        .collect();
-
-    let hash = poseidon.hash(&inputs).unwrap();
+/// Rejects private keys that are empty or made only of zero bytes: they hash to a valid but
+/// publicly known secret scalar, so anyone could recompute the identity.
+pub fn check_weak_key(private_key: &[u8]) -> Result<(), SemaphoreError> {
+    if private_key.iter().all(|&b| b == 0) {
+        return Err(SemaphoreError::WeakKey);
+    }
+    Ok(())
+}
 
-    hash.to_string()
+/// Coarse strength estimate of a private key, from `estimate_key_strength`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyStrength {
+    Weak,
+    Acceptable,
+    Strong,
 }
 
-fn string_to_bigint(num_str: &str) -> BigInt {
-    num_str
-        .parse()
-        .expect("Failed to parse the string into BigUint")
+/// Estimates how guessable a private key is from its length and byte diversity.
+///
+/// This is a heuristic for warning users, not a cryptographic entropy measure: a short or
+/// repetitive key is `Weak`, printable ASCII (a passphrase) is at most `Acceptable`, and only
+/// binary keys of at least 32 bytes with at least 20 distinct byte values are `Strong`. A key
+/// rated `Strong` can still be weak, e.g. if it was derived from a guessable value.
+pub fn estimate_key_strength(private_key: &[u8]) -> KeyStrength {
+    let mut seen = [false; 256];
+    for &b in private_key {
+        seen[b as usize] = true;
+    }
+    let distinct = seen.iter().filter(|&&s| s).count();
+
+    if private_key.len() < 16 || distinct < 8 {
+        KeyStrength::Weak
+    } else if private_key.len() >= 32 && distinct >= 20 && !private_key.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        KeyStrength::Strong
+    } else {
+        KeyStrength::Acceptable
+    }
 }
 
 /// Derives a secret scalar from a private key buffer.
+/// Fails with `SemaphoreError::WeakKey` for keys rejected by `check_weak_key`.
 pub fn derive_secret_scalar(private_key: &[u8]) -> Result<num_bigint::BigInt, Box<dyn Error>> {
-    let mut hash = hash_input(&private_key);
+    check_weak_key(private_key)?;
+    let mut hash = hash_input(private_key);
     hash.truncate(32);
-    prune_buffer(hash.clone());
-    Ok(shift_right(&le_bytes_to_bigint(&hash), &num_bigint::BigInt::from(3)) % &*SUBORDER)
+    #[cfg(feature = "trace")]
+    log::debug!("private key hash: {}", hex::encode(&hash));
+    clamp_scalar_bytes(&mut hash);
+    #[cfg(feature = "trace")]
+    log::debug!("clamped bytes: {}", hex::encode(&hash));
+    let secret_scalar = shift_right(&le_bytes_to_bigint(&hash), &num_bigint::BigInt::from(3)) % &*SUBORDER;
+    #[cfg(feature = "trace")]
+    log::debug!("secret scalar: {}", secret_scalar);
+    Ok(secret_scalar)
+}
+
+/// Derives the secret scalar the way releases before the clamping fix did, when the pruned
+/// hash was discarded and the raw hash was used. Differs from `derive_secret_scalar` whenever
+/// the top two bits of the 32nd hash byte are not `01`, so commitments stored by those releases
+/// can only be reproduced with this function. Do not use it for new identities.
+pub fn derive_secret_scalar_legacy(private_key: &[u8]) -> BigInt {
+    let mut hash = hash_input(private_key);
+    hash.truncate(32);
+    shift_right(&le_bytes_to_bigint(&hash), &BigInt::from(3)) % &*SUBORDER
 }
 
 /// Derives a public key (a Baby Jubjub point) from a private key buffer.
+/// Fails with `SemaphoreError::IdentityPublicKey` if the key derives a zero secret scalar.
 pub fn derive_public_key(private_key: &[u8]) -> Result<Point, Box<dyn Error>> {
+    public_key_from_generator(private_key, &BASE8)
+}
+
+/// Like `derive_public_key`, but multiplies the secret scalar with `generator` instead of
+/// `BASE8`. The generator must be in the prime-order subgroup and not the identity.
+pub fn derive_public_key_with_generator(private_key: &[u8], generator: &Point) -> Result<Point, Box<dyn Error>> {
+    validate_generator(generator)?;
+    public_key_from_generator(private_key, generator)
+}
+
+fn public_key_from_generator(private_key: &[u8], generator: &Point) -> Result<Point, Box<dyn Error>> {
     let s = derive_secret_scalar(private_key)?;
-    Ok(mul_point_escalar(&BASE8, s))
+    let public_key = mul_point_escalar(generator, s);
+    validate_public_key(&public_key)?;
+    Ok(public_key)
+}
+
+/// Checks that a caller-supplied generator is a non-identity point of the prime-order subgroup.
+fn validate_generator(generator: &Point) -> Result<(), SemaphoreError> {
+    if is_identity(generator) || !in_subgroup(generator) {
+        return Err(SemaphoreError::InvalidParameter(
+            "Generator must be a non-identity point of the prime-order subgroup".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if `private_key` derives exactly `public_key`.
+pub fn public_key_matches(private_key: &[u8], public_key: &Point) -> bool {
+    derive_public_key(private_key).is_ok_and(|derived| &derived == public_key)
+}
+
+/// Checks that a public key is on the curve and is not the identity point `(0, 1)`,
+/// which corresponds to a zero secret scalar and makes signatures forgeable.
+pub fn validate_public_key(public_key: &Point) -> Result<(), SemaphoreError> {
+    if !in_curve(public_key) {
+        return Err(SemaphoreError::InvalidPublicKey("Point is not on the curve".to_string()));
+    }
+    if is_identity(public_key) {
+        return Err(SemaphoreError::IdentityPublicKey);
+    }
+    Ok(())
+}
+
+/// Canonical encoding of a message as it is signed and verified: the bytes are read as a
+/// little-endian integer and re-encoded in exactly 32 bytes. Trailing zero bytes are thus
+/// ignored, so `[7]`, `[7, 0]` and `[7, 0, ..., 0]` are the same message, and the empty message
+/// is zero. Fails if the value does not fit in 32 bytes.
+pub fn canonicalize_message(message: &[u8]) -> Result<[u8; 32], SemaphoreError> {
+    let len = message.len().min(32);
+    if message[len..].iter().any(|&b| b != 0) {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "Message of {} bytes does not fit in 32 bytes",
+            message.len()
+        )));
+    }
+    let mut canonical = [0u8; 32];
+    canonical[..len].copy_from_slice(&message[..len]);
+    Ok(canonical)
 }
 
 /// Signs a message using the given private key and Poseidon hash.
 pub fn sign_message(private_key: &[u8], message: &[u8]) -> Result<Signature, Box<dyn Error>> {
-    let hash = hash_input(&private_key);
-    let s_bytes = &mut hash[..32].to_vec();
-    prune_buffer(s_bytes.to_vec());
-    let s = le_bytes_to_bigint(s_bytes);
-    let a = mul_point_escalar(&BASE8, shift_right(&s, &BigInt::from(3)));
-
-    let msg_bigint = le_bytes_to_bigint(message);
-    let msg_buff = le_bigint_to_bytes(&msg_bigint, Some(32))?;
+    sign_with_generator(private_key, message, &BASE8)
+}
+
+/// Like `sign_message`, but with `generator` in place of `BASE8` for both `A` and `R8`.
+/// Such signatures only verify with `verify_signature_with_generator` and the same generator.
+pub fn sign_message_with_generator(private_key: &[u8], message: &[u8], generator: &Point) -> Result<Signature, Box<dyn Error>> {
+    validate_generator(generator)?;
+    sign_with_generator(private_key, message, generator)
+}
+
+/// Like `sign_message`, but with the secret scalar of `derive_secret_scalar_legacy`, so the
+/// signature verifies under the public key of an identity derived that way. Do not use it for
+/// new identities.
+pub fn sign_message_legacy(private_key: &[u8], message: &[u8]) -> Result<Signature, Box<dyn Error>> {
+    check_weak_key(private_key)?;
+    let hash = hash_input(private_key);
+    sign_with_scalar(&hash, &derive_secret_scalar_legacy(private_key), message, &BASE8)
+}
+
+fn sign_with_generator(private_key: &[u8], message: &[u8], generator: &Point) -> Result<Signature, Box<dyn Error>> {
+    check_weak_key(private_key)?;
+    let hash = hash_input(private_key);
+    let mut s_bytes = hash[..32].to_vec();
+    clamp_scalar_bytes(&mut s_bytes);
+    let s = le_bytes_to_bigint(&s_bytes);
+    let secret_scalar = shift_right(&s, &BigInt::from(3));
+    sign_with_scalar(&hash, &secret_scalar, message, generator)
+}
+
+/// Signs with `secret_scalar`; the upper half of `hash`, the hashed private key, seeds the nonce.
+fn sign_with_scalar(hash: &[u8], secret_scalar: &BigInt, message: &[u8], generator: &Point) -> Result<Signature, Box<dyn Error>> {
+    let a = mul_point_escalar(generator, secret_scalar.clone());
+
+    let msg_buff = canonicalize_message(message)?;
     let r_buff = hash_input(&[&hash[32..64], &msg_buff].concat());
 
-    let r = Fr.e(le_bytes_to_bigint(&r_buff));
-    let r8 = mul_point_escalar(&BASE8, r.clone());
-    let message_bigint = le_bytes_to_bigint(message);
-    let hm = poseidon5(vec![
-        r8.0.to_string(),
-        r8.1.to_string(),
-        a.0.to_string(),
-        a.1.to_string(),
-        message_bigint.to_string(),
-    ]);
-    let hm_bigint = string_to_bigint(&hm);
-    let s_final = Fr.add(&r, &Fr.mul(&hm_bigint, &s));
+    // Signature scalars live in the prime-order subgroup.
+    let r = Fs.e(le_bytes_to_bigint(&r_buff));
+    let r8 = mul_point_escalar(generator, r.clone());
+    let hm_bigint = compute_challenge(&r8, &a, &le_bytes_to_bigint(&msg_buff));
+    let s_final = Fs.add(&r, &Fs.mul(&hm_bigint, &mul(secret_scalar, &BigInt::from(8))));
 
     Ok(Signature { r8, s: s_final })
 }
 
+/// Checks applied by `verify_signature_with_options` on top of the verification equation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Reject public keys outside the prime-order subgroup. On by default; turn it off only to
+    /// accept legacy signatures made with keys that carry a small-order component.
+    pub check_subgroup: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self { check_subgroup: true }
+    }
+}
+
 /// Verifies a signature against a given message and public key.
+/// Public keys outside the prime-order subgroup are rejected, see `VerifyOptions`.
 pub fn verify_signature(message: &[u8], signature: &Signature, public_key: &Point) -> Result<bool, Box<dyn Error>> {
+    verify_signature_with_options(message, signature, public_key, VerifyOptions::default())
+}
+
+/// Like `verify_signature`, with the checks selected by `options`.
+pub fn verify_signature_with_options(
+    message: &[u8],
+    signature: &Signature,
+    public_key: &Point,
+    options: VerifyOptions,
+) -> Result<bool, Box<dyn Error>> {
+    if options.check_subgroup && !in_subgroup(public_key) {
+        return Ok(false);
+    }
+    verify_with_generator(message, signature, public_key, &BASE8)
+}
+
+/// Verifies a signature produced by `sign_message_with_generator` with the same `generator`.
+pub fn verify_signature_with_generator(
+    message: &[u8],
+    signature: &Signature,
+    public_key: &Point,
+    generator: &Point,
+) -> Result<bool, Box<dyn Error>> {
+    validate_generator(generator)?;
+    verify_with_generator(message, signature, public_key, generator)
+}
+
+fn verify_with_generator(message: &[u8], signature: &Signature, public_key: &Point, generator: &Point) -> Result<bool, Box<dyn Error>> {
     if !in_curve(&signature.r8) || !in_curve(public_key) {
         return Ok(false);
     }
 
-    let message_bigint = le_bytes_to_bigint(message);
-    let hm = poseidon5(vec![
-        signature.r8.0.to_string(),
-        signature.r8.1.to_string(),
-        public_key.0.to_string(),
-        public_key.1.to_string(),
-        message_bigint.to_string(),
-    ]);
+    let message = canonicalize_message(message)?;
+    let hm_bigint = compute_challenge(&signature.r8, public_key, &le_bytes_to_bigint(&message));
 
+    Ok(verify_challenge(signature, public_key, &hm_bigint, generator))
+}
+
+/// Same result as `verify_signature`, but every check is evaluated and the results are
+/// combined at the end, so neither the outcome nor the running time reveals which input was
+/// malformed. Scalar multiplication itself is not constant time.
+pub fn verify_signature_ct(message: &[u8], signature: &Signature, public_key: &Point) -> bool {
+    let r8_valid = in_curve(&signature.r8);
+    let public_key_valid = in_subgroup(public_key);
+
+    let message = canonicalize_message(message);
+    let message_valid = message.is_ok();
+    let message = le_bytes_to_bigint(&message.unwrap_or_default());
+    let hm = compute_challenge(&signature.r8, public_key, &message);
     let p_left = mul_point_escalar(&BASE8, signature.s.clone());
-    let hm_bigint = string_to_bigint(&hm);
-    let p_right = add_point(&signature.r8, &mul_point_escalar(public_key, mul(&hm_bigint, &num_bigint::BigInt::from(8))));
+    let p_right = add_point(&signature.r8, &mul_point_escalar(public_key, challenge_scalar(&hm)));
+    let equation_holds = Fr.eq(&p_left.0, &p_right.0) & Fr.eq(&p_left.1, &p_right.1);
+
+    message_valid & r8_valid & public_key_valid & equation_holds
+}
+
+/// Left-hand side of the verification equation, `S * B8`.
+pub fn verification_lhs(signature: &Signature) -> Point {
+    mul_point_escalar(&BASE8, signature.s.clone())
+}
+
+/// Right-hand side of the verification equation, `R8 + 8 * hm * A`, with `hm` the challenge
+/// of `message` as an integer. A signature is valid when both sides are equal and its points
+/// are on the curve.
+pub fn verification_rhs(signature: &Signature, public_key: &Point, message: &BigInt) -> Point {
+    let hm = compute_challenge(&signature.r8, public_key, message);
+    add_point(&signature.r8, &mul_point_escalar(public_key, challenge_scalar(&hm)))
+}
+
+/// Computes the signature challenge `hm = poseidon([R8.x, R8.y, A.x, A.y, message])`.
+pub fn compute_challenge(r8: &Point, public_key: &Point, message: &BigInt) -> BigInt {
+    poseidon(&[r8.0.clone(), r8.1.clone(), public_key.0.clone(), public_key.1.clone(), message.clone()])
+        .expect("Poseidon supports five inputs")
+}
+
+/// Returns `8 * hm` reduced modulo the curve order `ORDER`, the scalar applied to the public
+/// key when verifying. The order of every curve point divides `ORDER`, so the multiple of the
+/// key is unchanged, even for keys with a small-order component, and the scalar multiplication
+/// runs over at most 254 bits instead of 257.
+pub(crate) fn challenge_scalar(hm: &BigInt) -> BigInt {
+    utils::scalar::reduce(&mul(hm, &BigInt::from(8)), &ORDER)
+}
+
+/// Verifies a signature against an externally computed challenge `hm`, checking
+/// `S*B8 == R8 + 8*hm*A` without hashing the message. Useful when the challenge comes from a
+/// circuit and must be checked for consistency.
+pub fn verify_signature_with_challenge(signature: &Signature, public_key: &Point, hm: &BigInt) -> bool {
+    verify_challenge(signature, public_key, hm, &BASE8)
+}
+
+fn verify_challenge(signature: &Signature, public_key: &Point, hm: &BigInt, generator: &Point) -> bool {
+    if !in_curve(&signature.r8) || !in_curve(public_key) {
+        return false;
+    }
+
+    let p_left = mul_point_escalar(generator, signature.s.clone());
+    let p_right = add_point(&signature.r8, &mul_point_escalar(public_key, challenge_scalar(hm)));
 
-    Ok(Fr.eq(&p_left.0, &p_right.0) && Fr.eq(&p_left.1, &p_right.1))
+    Fr.eq(&p_left.0, &p_right.0) && Fr.eq(&p_left.1, &p_right.1)
+}
+
+/// Derives a short 20-byte identifier from a public key, Ethereum-style: the last 20 bytes of
+/// Keccak256 over the 32-byte little-endian packed key. This is only an identifier; it is not
+/// an Ethereum account and no secp256k1 key controls it.
+///
+/// # Panics
+/// Panics if the point cannot be packed into 32 bytes, i.e. its `y` coordinate is not reduced.
+#[cfg(feature = "keccak")]
+pub fn public_key_to_address(public_key: &Point) -> [u8; 20] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let packed = le_bigint_to_bytes(&pack_point(public_key), Some(32)).expect("packed point fits in 32 bytes");
+    let mut hasher = Keccak::v256();
+    let mut digest = [0u8; 32];
+    hasher.update(&packed);
+    hasher.finalize(&mut digest);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&digest[12..]);
+    address
+}
+
+/// Verifies `(message, signature, public_key)` items in parallel, returning one result per item
+/// in input order. Items whose verification errors are reported as `false`.
+#[cfg(feature = "parallel")]
+pub fn verify_signatures_parallel(items: &[(Vec<u8>, Signature, Point)]) -> Vec<bool> {
+    use rayon::prelude::*;
+
+    items
+        .par_iter()
+        .map(|(message, signature, public_key)| verify_signature(message, signature, public_key).unwrap_or(false))
+        .collect()
+}
+
+/// Verifies `(message, signature, public_key)` items and returns the indices of those that do
+/// not verify, in increasing order; an empty result means every item is valid. Items whose
+/// verification errors count as failures.
+pub fn verify_all(items: &[(Vec<u8>, Signature, Point)]) -> Vec<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, (message, signature, public_key))| !verify_signature(message, signature, public_key).unwrap_or(false))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Verifies a signature against a packed public key, unpacking it first.
+pub fn verify_signature_packed(message: &[u8], signature: &Signature, packed_pk: &BigInt) -> Result<bool, SemaphoreError> {
+    let public_key = unpack_point(packed_pk)
+        .ok_or_else(|| SemaphoreError::InvalidPublicKey(format!("Cannot unpack {}", packed_pk)))?;
+    verify_signature(message, signature, &public_key).map_err(|e| SemaphoreError::InvalidMessage(e.to_string()))
+}
+
+/// Checks that a message is a canonical field element, i.e. `0 <= message < R`.
+pub fn validate_message(message: &BigInt) -> Result<(), SemaphoreError> {
+    if message.sign() == num_bigint::Sign::Minus || message >= &*R {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "{} is not a field element",
+            message
+        )));
+    }
+    Ok(())
+}
+
+/// Signs a field element message using the given private key.
+pub fn sign_message_bigint(private_key: &[u8], message: &BigInt) -> Result<Signature, SemaphoreError> {
+    validate_message(message)?;
+    let message_bytes = le_bigint_to_bytes(message, Some(32)).map_err(SemaphoreError::InvalidMessage)?;
+    sign_message(private_key, &message_bytes).map_err(|e| SemaphoreError::InvalidMessage(e.to_string()))
+}
+
+/// Verifies a signature over a field element message.
+pub fn verify_signature_bigint(message: &BigInt, signature: &Signature, public_key: &Point) -> Result<bool, SemaphoreError> {
+    validate_message(message)?;
+    let message_bytes = le_bigint_to_bytes(message, Some(32)).map_err(SemaphoreError::InvalidMessage)?;
+    verify_signature(&message_bytes, signature, public_key).map_err(|e| SemaphoreError::InvalidMessage(e.to_string()))
+}
+
+/// Like `sign_message`, but reads `message` as a big-endian integer (as `be_bytes_to_bigint`
+/// does) instead of little-endian. The same bytes give different messages in the two byte
+/// orders, so a signature made with one only verifies with the verifier of the same order:
+/// pair this with `verify_signature_be`.
+pub fn sign_message_be(private_key: &[u8], message: &[u8]) -> Result<Signature, Box<dyn Error>> {
+    sign_message(private_key, &be_to_le(message))
+}
+
+/// Like `verify_signature`, but reads `message` as a big-endian integer, see `sign_message_be`.
+pub fn verify_signature_be(message: &[u8], signature: &Signature, public_key: &Point) -> Result<bool, Box<dyn Error>> {
+    verify_signature(&be_to_le(message), signature, public_key)
+}
+
+/// Reversing the bytes of a big-endian integer gives its little-endian encoding.
+fn be_to_le(message: &[u8]) -> Vec<u8> {
+    message.iter().rev().copied().collect()
+}
+
+/// Hashes a tuple of field element messages into a single field element with Poseidon.
+/// Between 1 and 12 messages are supported.
+pub fn hash_messages(messages: &[BigInt]) -> Result<BigInt, SemaphoreError> {
+    if messages.is_empty() || messages.len() > 12 {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "Expected between 1 and 12 messages, got {}",
+            messages.len()
+        )));
+    }
+    for message in messages {
+        validate_message(message)?;
+    }
+
+    poseidon(messages)
+}
+
+/// Signs a tuple of field element messages under a single signature.
+/// The messages are hashed with `hash_messages`, so their order matters.
+pub fn sign_messages(private_key: &[u8], messages: &[BigInt]) -> Result<Signature, SemaphoreError> {
+    sign_message_bigint(private_key, &hash_messages(messages)?)
+}
+
+/// Verifies a signature produced by `sign_messages`.
+pub fn verify_messages(messages: &[BigInt], signature: &Signature, public_key: &Point) -> Result<bool, SemaphoreError> {
+    verify_signature_bigint(&hash_messages(messages)?, signature, public_key)
+}
+
+/// Domain tag of `hash_abi`, read as a big-endian integer (21 bytes, so below `R`).
+pub const ABI_DOMAIN_TAG: &[u8] = b"eddsa-poseidon-abi-v1";
+
+/// Hashes a tuple of fields, such as the `uint256` words of an ABI-encoded call, into the
+/// message signed by `sign_abi`: `poseidon([tag, fields[0], .., fields[n - 1]])` with `tag`
+/// the big-endian integer of `ABI_DOMAIN_TAG`. The tag keeps these messages apart from those
+/// of `hash_messages`. Between 1 and 11 fields are supported, and each must be a field element:
+/// `uint256` values of `R` or more have to be reduced or split by the caller.
+pub fn hash_abi(fields: &[BigInt]) -> Result<BigInt, SemaphoreError> {
+    if fields.is_empty() || fields.len() > 11 {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "Expected between 1 and 11 fields, got {}",
+            fields.len()
+        )));
+    }
+    for field in fields {
+        validate_message(field)?;
+    }
+
+    let mut inputs = Vec::with_capacity(fields.len() + 1);
+    inputs.push(BigInt::from_bytes_be(num_bigint::Sign::Plus, ABI_DOMAIN_TAG));
+    inputs.extend_from_slice(fields);
+    poseidon(&inputs)
+}
+
+/// Signs a tuple of fields hashed with `hash_abi`, so their order matters.
+pub fn sign_abi(private_key: &[u8], fields: &[BigInt]) -> Result<Signature, SemaphoreError> {
+    sign_message_bigint(private_key, &hash_abi(fields)?)
+}
+
+/// Verifies a signature produced by `sign_abi`.
+pub fn verify_abi(fields: &[BigInt], signature: &Signature, public_key: &Point) -> Result<bool, SemaphoreError> {
+    verify_signature_bigint(&hash_abi(fields)?, signature, public_key)
 }
 
 /// Packs a public key into a compressed format (bigint).
@@ -110,9 +498,77 @@ pub fn pack_public_key(public_key: &Point) -> Result<num_bigint::BigInt, Box<dyn
     Ok(pack_point(public_key))
 }
 
-/// Unpacks a compressed public key.
+/// Unpacks a public key packed by `pack_public_key`. Fails with `SemaphoreError::InvalidLength`
+/// if the value does not fit in 32 bytes, and with `SemaphoreError::InvalidPoint` if it does
+/// but is not the packing of a curve point.
 pub fn unpack_public_key(packed: &num_bigint::BigInt) -> Result<Point, Box<dyn Error>> {
-    unpack_point(packed).ok_or_else(|| "Invalid public key".into())
+    if packed.sign() == num_bigint::Sign::Minus {
+        return Err(SemaphoreError::InvalidPoint(format!("Packed key {} is negative", packed)).into());
+    }
+    let length = packed.bits().div_ceil(8) as usize;
+    if length > 32 {
+        return Err(SemaphoreError::InvalidLength { expected: 32, actual: length }.into());
+    }
+    unpack_point(packed).ok_or_else(|| {
+        SemaphoreError::InvalidPoint(format!("Packed key {} is not a curve point", packed)).into()
+    })
+}
+
+/// Packs public keys into one buffer: the key count as a 4-byte little-endian integer,
+/// followed by each key packed into 32 little-endian bytes, in order.
+pub fn pack_public_keys(keys: &[Point]) -> Result<Vec<u8>, SemaphoreError> {
+    let count = u32::try_from(keys.len())
+        .map_err(|_| SemaphoreError::InvalidParameter("Too many public keys to pack".to_string()))?;
+    let mut packed = Vec::with_capacity(4 + 32 * keys.len());
+    packed.extend_from_slice(&count.to_le_bytes());
+    for (i, key) in keys.iter().enumerate() {
+        validate_public_key(key)
+            .map_err(|e| SemaphoreError::InvalidPublicKey(format!("key {}: {}", i, e)))?;
+        let bytes = le_bigint_to_bytes(&pack_point(key), Some(32)).map_err(SemaphoreError::InvalidPublicKey)?;
+        packed.extend_from_slice(&bytes);
+    }
+    Ok(packed)
+}
+
+/// Reverses `pack_public_keys`, validating the length prefix and every key.
+pub fn unpack_public_keys(packed: &[u8]) -> Result<Vec<Point>, SemaphoreError> {
+    let (count, keys) = packed
+        .split_first_chunk::<4>()
+        .ok_or_else(|| SemaphoreError::InvalidParameter("Missing key count".to_string()))?;
+    let count = u32::from_le_bytes(*count) as usize;
+    if keys.len() != count.saturating_mul(32) {
+        return Err(SemaphoreError::InvalidParameter(format!(
+            "Expected {} packed keys ({} bytes), got {} bytes",
+            count,
+            count.saturating_mul(32),
+            keys.len()
+        )));
+    }
+
+    keys.chunks(32)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let key = unpack_point(&le_bytes_to_bigint(chunk))
+                .ok_or_else(|| SemaphoreError::InvalidPublicKey(format!("key {} does not unpack", i)))?;
+            validate_public_key(&key)?;
+            Ok(key)
+        })
+        .collect()
+}
+
+/// Compares two packed public keys in constant time over their 32-byte encodings.
+/// Prefer this over `==` when looking up a packed key in a store, so the comparison
+/// time does not reveal how many leading bytes matched.
+/// Values that do not fit in 32 bytes never compare equal.
+pub fn ct_eq_packed(a: &BigInt, b: &BigInt) -> bool {
+    let (Ok(a_bytes), Ok(b_bytes)) = (le_bigint_to_bytes(a, Some(32)), le_bigint_to_bytes(b, Some(32))) else {
+        return false;
+    };
+    let diff = a_bytes
+        .iter()
+        .zip(b_bytes.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    diff == 0
 }
 
 /// Packs a signature into 64-byte format.
@@ -127,6 +583,7 @@ pub fn pack_signature(sig: &Signature) -> Result<Vec<u8>, Box<dyn Error>> {
 }
 
 /// Unpacks a signature from 64-byte format.
+/// Fails with `SemaphoreError::InvalidScalar` if `S` is not lower than the subgroup order.
 pub fn unpack_signature(packed: &[u8]) -> Result<Signature, Box<dyn Error>> {
     if packed.len() != 64 {
         return Err("Packed signature must be 64 bytes".into());
@@ -134,6 +591,13 @@ pub fn unpack_signature(packed: &[u8]) -> Result<Signature, Box<dyn Error>> {
     let r8 = unpack_point(&le_bytes_to_bigint(&packed[..32]))
         .ok_or_else(|| format!("Invalid packed R8 in signature: {}", hex::encode(&packed[..32])))?;
     let s = le_bytes_to_bigint(&packed[32..]);
+    if s >= *SUBORDER {
+        return Err(SemaphoreError::InvalidScalar(format!(
+            "'S' = 0x{} is not lower than the subgroup order",
+            hex::encode(&packed[32..])
+        ))
+        .into());
+    }
     Ok(Signature { r8, s })
 }
 
@@ -141,6 +605,145 @@ pub fn unpack_signature(packed: &[u8]) -> Result<Signature, Box<dyn Error>> {
 mod tests {
     use super::*;
     use rand::Rng;
+    use utils::testing::assert_field_eq;
+
+    /// Signs `message` under `secret_scalar` with `A + (0, -1)` as the public key, the way a
+    /// key with a 2-torsion component would. Returns the signature and that key.
+    fn sign_with_torsion_key(secret_scalar: &BigInt, message: &[u8; 32]) -> (Signature, Point) {
+        let public_key = add_point(&mul_point_escalar(&BASE8, secret_scalar.clone()), &(Fr.zero.clone(), Fr.negone.clone()));
+        let r = BigInt::from(123456789);
+        let r8 = mul_point_escalar(&BASE8, r.clone());
+        let hm = compute_challenge(&r8, &public_key, &le_bytes_to_bigint(message));
+        let s = Fs.add(&r, &Fs.mul(&hm, &(secret_scalar * 8)));
+        (Signature { r8, s }, public_key)
+    }
+
+    #[test]
+    fn test_verify_all_reports_failing_indices() {
+        let mut items: Vec<(Vec<u8>, Signature, Point)> = (1..=5u8)
+            .map(|i| {
+                let message = vec![i; 32];
+                let signature = sign_message(&[i; 32], &message).unwrap();
+                (message, signature, derive_public_key(&[i; 32]).unwrap())
+            })
+            .collect();
+        assert!(verify_all(&items).is_empty());
+
+        items[3].0 = vec![9u8; 32];
+        assert_eq!(verify_all(&items), vec![3]);
+
+        // A message that does not fit in 32 bytes makes verification error, which counts as a
+        // failure too.
+        items[1].0 = vec![0xffu8; 33];
+        assert!(verify_signature(&items[1].0, &items[1].1, &items[1].2).is_err());
+        assert_eq!(verify_all(&items), vec![1, 3]);
+        assert!(verify_all(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_sign_abi() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        // An (amount, recipient) pair of uint256 words.
+        let fields = [BigInt::from(1_000_000u64), BigInt::from(0xdead_beefu64)];
+        let signature = sign_abi(b"secret", &fields).unwrap();
+        assert!(verify_abi(&fields, &signature, &public_key).unwrap());
+
+        let reordered = [fields[1].clone(), fields[0].clone()];
+        assert!(!verify_abi(&reordered, &signature, &public_key).unwrap());
+        assert!(!verify_messages(&fields, &signature, &public_key).unwrap());
+        assert!(!verify_abi(&fields[..1], &signature, &public_key).unwrap());
+
+        assert_eq!(
+            hash_abi(&fields).unwrap().to_string(),
+            "790634540840852497217962697127256905737006552677603307599225284533317290303"
+        );
+        assert!(hash_abi(&[]).is_err());
+        assert!(hash_abi(&vec![BigInt::from(1); 12]).is_err());
+        assert!(hash_abi(&vec![BigInt::from(1); 11]).is_ok());
+        assert!(sign_abi(b"secret", &[&*R + 1]).is_err());
+    }
+
+    #[test]
+    fn test_big_endian_messages() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        let message: Vec<u8> = (1..=32).collect();
+
+        let signature = sign_message_be(b"secret", &message).unwrap();
+        assert!(verify_signature_be(&message, &signature, &public_key).unwrap());
+        assert!(!verify_signature(&message, &signature, &public_key).unwrap());
+        let value = utils::conversions::be_bytes_to_bigint(&message);
+        assert!(verify_signature_bigint(&value, &signature, &public_key).unwrap());
+
+        let signature = sign_message(b"secret", &message).unwrap();
+        assert!(verify_signature(&message, &signature, &public_key).unwrap());
+        assert!(!verify_signature_be(&message, &signature, &public_key).unwrap());
+
+        // Short messages are small integers in both orders.
+        let signature = sign_message_be(b"secret", &[0, 0, 1]).unwrap();
+        assert!(verify_signature_bigint(&BigInt::from(1), &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_reduced_challenge_scalar_gives_same_point() {
+        let message = [2u8; 32];
+        let signature = sign_message(b"secret", &message).unwrap();
+        let public_key = derive_public_key(b"secret").unwrap();
+        let (torsion_signature, torsion_key) = sign_with_torsion_key(&derive_secret_scalar(b"secret").unwrap(), &message);
+
+        for (signature, key) in [(&signature, &public_key), (&torsion_signature, &torsion_key)] {
+            let hm = compute_challenge(&signature.r8, key, &le_bytes_to_bigint(&message));
+            let unreduced = &hm * BigInt::from(8);
+            let reduced = challenge_scalar(&hm);
+            assert!(reduced < *ORDER);
+            assert_eq!(mul_point_escalar(key, reduced.clone()), mul_point_escalar(key, unreduced.clone()));
+
+            let lhs = mul_point_escalar(&BASE8, signature.s.clone());
+            let unreduced_valid = lhs == add_point(&signature.r8, &mul_point_escalar(key, unreduced));
+            assert!(unreduced_valid);
+            assert_eq!(verify_challenge(signature, key, &hm, &BASE8), unreduced_valid);
+        }
+        assert_eq!(challenge_scalar(&(&*ORDER + 1)), BigInt::from(8));
+    }
+
+    #[test]
+    fn test_verify_signature_with_options_subgroup_check() {
+        let message = [2u8; 32];
+        let secret_scalar = derive_secret_scalar(b"secret").unwrap();
+        let (signature, torsion_key) = sign_with_torsion_key(&secret_scalar, &message);
+        assert!(in_curve(&torsion_key));
+        assert!(!in_subgroup(&torsion_key));
+
+        let relaxed = VerifyOptions { check_subgroup: false };
+        assert!(verify_signature_with_options(&message, &signature, &torsion_key, relaxed).unwrap());
+        assert!(!verify_signature_with_options(&message, &signature, &torsion_key, VerifyOptions::default()).unwrap());
+        assert!(!verify_signature(&message, &signature, &torsion_key).unwrap());
+        assert!(!verify_signature_ct(&message, &signature, &torsion_key));
+        assert!(!verify_signature_with_options(&[3u8; 32], &signature, &torsion_key, relaxed).unwrap());
+
+        // Subgroup keys verify the same way with either setting.
+        let public_key = derive_public_key(b"secret").unwrap();
+        let signature = sign_message(b"secret", &message).unwrap();
+        for check_subgroup in [true, false] {
+            let options = VerifyOptions { check_subgroup };
+            assert!(verify_signature_with_options(&message, &signature, &public_key, options).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_estimate_key_strength() {
+        use rand::Rng;
+
+        assert_eq!(estimate_key_strength(b"secret"), KeyStrength::Weak);
+        assert_eq!(estimate_key_strength(&[]), KeyStrength::Weak);
+        assert_eq!(estimate_key_strength(&[7u8; 64]), KeyStrength::Weak);
+        assert_eq!(estimate_key_strength(b"correct horse battery staple, twice"), KeyStrength::Acceptable);
+        let counting: Vec<u8> = (0..20).collect();
+        assert_eq!(estimate_key_strength(&counting), KeyStrength::Acceptable);
+
+        let mut key = [0u8; 32];
+        rand::rng().fill(&mut key);
+        assert_eq!(estimate_key_strength(&key), KeyStrength::Strong);
+    }
 
     #[test]
     fn test_derive_public_key_from_string() {
@@ -152,15 +755,297 @@ mod tests {
         assert!(in_curve(&public_key));
     }
 
-    // #[test]
-    // fn test_sign_and_verify_message_bigint() {
-    //     let private_key = b"secret";
-    //     let message = BigInt::from(2);
-    //     let public_key = derive_public_key(private_key).unwrap();
-    //     let signature = sign_message(private_key, &le_bigint_to_bytes(&message, Some(32)).unwrap()).unwrap();
-    //     let verified = verify_signature(&le_bigint_to_bytes(&message, Some(32)).unwrap(), &signature, &public_key).unwrap();
-    //     assert!(verified);
-    // }
+    #[test]
+    fn test_sign_and_verify_message_bigint() {
+        let private_key = b"secret";
+        let message = BigInt::from(2);
+        let public_key = derive_public_key(private_key).unwrap();
+        let signature = sign_message_bigint(private_key, &message).unwrap();
+        assert!(verify_signature_bigint(&message, &signature, &public_key).unwrap());
+        assert!(!verify_signature_bigint(&BigInt::from(3), &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_compute_challenge_satisfies_verification_equation() {
+        let private_key = b"secret";
+        let message = le_bigint_to_bytes(&BigInt::from(7), Some(32)).unwrap();
+        let public_key = derive_public_key(private_key).unwrap();
+        let signature = sign_message(private_key, &message).unwrap();
+        assert!(verify_signature(&message, &signature, &public_key).unwrap());
+
+        let hm = compute_challenge(&signature.r8, &public_key, &le_bytes_to_bigint(&message));
+        let lhs = mul_point_escalar(&BASE8, signature.s.clone());
+        let rhs = add_point(&signature.r8, &mul_point_escalar(&public_key, hm * 8));
+        assert_eq!(lhs, rhs);
+    }
+
+    #[test]
+    fn test_verify_signature_ct_agrees_with_verify_signature() {
+        let private_key = b"secret";
+        let message = le_bigint_to_bytes(&BigInt::from(2), Some(32)).unwrap();
+        let public_key = derive_public_key(private_key).unwrap();
+        let signature = sign_message(private_key, &message).unwrap();
+
+        let mut bad_r8 = signature.clone();
+        bad_r8.r8.1 += 1;
+        let mut bad_s = signature.clone();
+        bad_s.s += 1;
+        let mut bad_public_key = public_key.clone();
+        bad_public_key.0 += 1;
+        let other_message = le_bigint_to_bytes(&BigInt::from(3), Some(32)).unwrap();
+
+        let cases = [
+            (&message, &signature, &public_key),
+            (&message, &bad_r8, &public_key),
+            (&message, &bad_s, &public_key),
+            (&message, &signature, &bad_public_key),
+            (&other_message, &signature, &public_key),
+        ];
+        for (i, (message, signature, public_key)) in cases.into_iter().enumerate() {
+            let expected = verify_signature(message, signature, public_key).unwrap();
+            assert_eq!(verify_signature_ct(message, signature, public_key), expected, "case {}", i);
+            assert_eq!(expected, i == 0);
+        }
+    }
+
+    #[test]
+    fn test_verification_sides_match_for_valid_signature() {
+        let private_key = b"secret";
+        let message = BigInt::from(2);
+        let public_key = derive_public_key(private_key).unwrap();
+        let signature = sign_message_bigint(private_key, &message).unwrap();
+
+        let lhs = verification_lhs(&signature);
+        assert_eq!(lhs, verification_rhs(&signature, &public_key, &message));
+        assert_ne!(lhs, verification_rhs(&signature, &public_key, &BigInt::from(3)));
+    }
+
+    #[test]
+    fn test_verify_signature_with_challenge() {
+        let private_key = b"secret";
+        let message = BigInt::from(2);
+        let public_key = derive_public_key(private_key).unwrap();
+        let signature = sign_message_bigint(private_key, &message).unwrap();
+        let hm = compute_challenge(&signature.r8, &public_key, &message);
+        assert_eq!(
+            hm,
+            poseidon(&[
+                signature.r8.0.clone(),
+                signature.r8.1.clone(),
+                public_key.0.clone(),
+                public_key.1.clone(),
+                message,
+            ])
+            .unwrap()
+        );
+
+        assert!(verify_signature_with_challenge(&signature, &public_key, &hm));
+        assert!(!verify_signature_with_challenge(&signature, &public_key, &(hm + 1)));
+    }
+
+    #[test]
+    fn test_with_generator_variants() {
+        let private_key = b"secret";
+        let message = le_bigint_to_bytes(&BigInt::from(2), Some(32)).unwrap();
+        assert_eq!(
+            derive_public_key_with_generator(private_key, &BASE8).unwrap(),
+            derive_public_key(private_key).unwrap()
+        );
+        let signature = sign_message_with_generator(private_key, &message, &BASE8).unwrap();
+        assert_eq!(signature, sign_message(private_key, &message).unwrap());
+
+        // Another subgroup generator gives a different but self-consistent key pair.
+        let generator = mul_point_escalar(&BASE8, BigInt::from(5));
+        let public_key = derive_public_key_with_generator(private_key, &generator).unwrap();
+        assert_ne!(public_key, derive_public_key(private_key).unwrap());
+        let signature = sign_message_with_generator(private_key, &message, &generator).unwrap();
+        assert!(verify_signature_with_generator(&message, &signature, &public_key, &generator).unwrap());
+        assert!(!verify_signature(&message, &signature, &public_key).unwrap());
+
+        // The 2-torsion point (0, -1) is on the curve but outside the subgroup; the identity is in
+        // it but generates nothing. Both are rejected.
+        let off_subgroup = (Fr.zero.clone(), Fr.neg(&Fr.one));
+        for generator in [off_subgroup, (Fr.zero.clone(), Fr.one.clone())] {
+            let err = derive_public_key_with_generator(private_key, &generator).unwrap_err();
+            assert!(matches!(err.downcast_ref::<SemaphoreError>(), Some(SemaphoreError::InvalidParameter(_))));
+            assert!(sign_message_with_generator(private_key, &message, &generator).is_err());
+        }
+    }
+
+    #[test]
+    fn test_pack_public_keys_round_trip() {
+        let keys: Vec<Point> = (1..=5u8).map(|i| derive_public_key(&[i; 32]).unwrap()).collect();
+        let packed = pack_public_keys(&keys).unwrap();
+        assert_eq!(packed.len(), 4 + 5 * 32);
+        assert_eq!(unpack_public_keys(&packed).unwrap(), keys);
+        assert!(unpack_public_keys(&pack_public_keys(&[]).unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unpack_public_keys_rejects_truncated_buffer() {
+        let keys: Vec<Point> = (1..=5u8).map(|i| derive_public_key(&[i; 32]).unwrap()).collect();
+        let packed = pack_public_keys(&keys).unwrap();
+        assert!(matches!(
+            unpack_public_keys(&packed[..packed.len() - 1]),
+            Err(SemaphoreError::InvalidParameter(_))
+        ));
+        assert!(unpack_public_keys(&packed[..3]).is_err());
+
+        let mut extra = packed.clone();
+        extra.extend_from_slice(&[0u8; 32]);
+        assert!(unpack_public_keys(&extra).is_err());
+    }
+
+    #[test]
+    fn test_differently_padded_messages_verify_consistently() {
+        let private_key = b"secret";
+        let public_key = derive_public_key(private_key).unwrap();
+        let padded = |len: usize| {
+            let mut message = vec![0u8; len];
+            message[0] = 7;
+            message
+        };
+        let encodings = [padded(1), padded(31), padded(32), padded(40)];
+        for encoding in &encodings {
+            assert_eq!(canonicalize_message(encoding).unwrap(), canonicalize_message(&encodings[2]).unwrap());
+        }
+
+        let signature = sign_message(private_key, &encodings[1]).unwrap();
+        for encoding in &encodings {
+            assert_eq!(&sign_message(private_key, encoding).unwrap(), &signature);
+            assert!(verify_signature(encoding, &signature, &public_key).unwrap());
+            assert!(verify_signature_ct(encoding, &signature, &public_key));
+        }
+
+        assert_eq!(canonicalize_message(&[]).unwrap(), [0u8; 32]);
+        assert!(sign_message(private_key, &[]).is_ok());
+        let mut too_long = vec![0u8; 33];
+        too_long[32] = 1;
+        assert!(matches!(canonicalize_message(&too_long), Err(SemaphoreError::InvalidMessage(_))));
+        assert!(verify_signature(&too_long, &signature, &public_key).is_err());
+        assert!(!verify_signature_ct(&too_long, &signature, &public_key));
+    }
+
+    #[test]
+    fn test_sign_and_verify_messages() {
+        let private_key = b"secret";
+        let public_key = derive_public_key(private_key).unwrap();
+        let vote = vec![BigInt::from(1), BigInt::from(2), BigInt::from(3)];
+        let signature = sign_messages(private_key, &vote).unwrap();
+        assert!(verify_messages(&vote, &signature, &public_key).unwrap());
+
+        let reordered = vec![BigInt::from(2), BigInt::from(1), BigInt::from(3)];
+        assert!(!verify_messages(&reordered, &signature, &public_key).unwrap());
+    }
+
+    #[test]
+    fn test_sign_messages_rejects_invalid_input() {
+        assert!(sign_messages(b"secret", &[]).is_err());
+        assert!(sign_messages(b"secret", &[BigInt::from(1), R.clone()]).is_err());
+    }
+
+    #[test]
+    fn test_verify_signature_packed() {
+        let private_key = b"secret";
+        let message = le_bigint_to_bytes(&BigInt::from(2), Some(32)).unwrap();
+        let public_key = derive_public_key(private_key).unwrap();
+        let packed = pack_public_key(&public_key).unwrap();
+        let signature = sign_message(private_key, &message).unwrap();
+
+        assert_eq!(
+            verify_signature_packed(&message, &signature, &packed).unwrap(),
+            verify_signature(&message, &signature, &public_key).unwrap()
+        );
+        assert!(verify_signature_packed(&message, &signature, &packed).unwrap());
+
+        let other = le_bigint_to_bytes(&BigInt::from(3), Some(32)).unwrap();
+        assert!(!verify_signature_packed(&other, &signature, &packed).unwrap());
+
+        let invalid = BigInt::from(1) << 300u32;
+        assert!(matches!(
+            verify_signature_packed(&message, &signature, &invalid),
+            Err(SemaphoreError::InvalidPublicKey(_))
+        ));
+    }
+
+    #[cfg(feature = "keccak")]
+    #[test]
+    fn test_public_key_to_address() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        let address = public_key_to_address(&public_key);
+        assert_eq!(hex::encode(address), "c3faddf137eee3daa6b0268156151f0c9a7e944a");
+        assert_eq!(public_key_to_address(&public_key), address);
+        assert_ne!(public_key_to_address(&BASE8), address);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_verify_signatures_parallel() {
+        let items: Vec<(Vec<u8>, Signature, Point)> = (0..6u8)
+            .map(|i| {
+                let private_key = [i + 1; 32];
+                let message = vec![i; 32];
+                let signature = sign_message(&private_key, &message).unwrap();
+                let public_key = derive_public_key(&private_key).unwrap();
+                // Every odd item is verified against the wrong message.
+                let message = if i % 2 == 1 { vec![i + 1; 32] } else { message };
+                (message, signature, public_key)
+            })
+            .collect();
+
+        let serial: Vec<bool> = items
+            .iter()
+            .map(|(m, s, p)| verify_signature(m, s, p).unwrap())
+            .collect();
+        assert_eq!(verify_signatures_parallel(&items), serial);
+        assert_eq!(serial, vec![true, false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_validate_message() {
+        assert!(validate_message(&BigInt::from(0)).is_ok());
+        assert!(validate_message(&(&*R - 1)).is_ok());
+        assert!(matches!(validate_message(&R), Err(SemaphoreError::InvalidMessage(_))));
+        assert!(validate_message(&BigInt::from(-1)).is_err());
+        assert!(sign_message_bigint(b"secret", &R).is_err());
+    }
+
+    #[test]
+    fn test_derive_secret_scalar_is_clamped() {
+        let mut hash = hash_input(b"secret");
+        hash.truncate(32);
+        let unclamped = le_bytes_to_bigint(&hash);
+        clamp_scalar_bytes(&mut hash);
+        let clamped = le_bytes_to_bigint(&hash);
+
+        assert_ne!(clamped, unclamped);
+        assert!(clamped.bit(254) && !clamped.bit(255));
+        assert_eq!(
+            derive_secret_scalar(b"secret").unwrap(),
+            shift_right(&clamped, &BigInt::from(3)) % &*SUBORDER
+        );
+    }
+
+    #[test]
+    fn test_public_key_matches() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        assert!(public_key_matches(b"secret", &public_key));
+        assert!(!public_key_matches(b"other", &public_key));
+    }
+
+    #[test]
+    fn test_validate_public_key() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        assert!(validate_public_key(&public_key).is_ok());
+
+        // Only a private key hashing to a zero secret scalar derives the identity.
+        let identity = (BigInt::from(0), BigInt::from(1));
+        assert_eq!(validate_public_key(&identity), Err(SemaphoreError::IdentityPublicKey));
+        assert!(matches!(
+            validate_public_key(&(BigInt::from(0), BigInt::from(3))),
+            Err(SemaphoreError::InvalidPublicKey(_))
+        ));
+    }
 
     #[test]
     fn test_pack_and_unpack_public_key() {
@@ -168,8 +1053,44 @@ mod tests {
         let public_key = derive_public_key(private_key).unwrap();
         let packed = pack_public_key(&public_key).unwrap();
         let unpacked = unpack_public_key(&packed).unwrap();
-        assert_eq!(public_key.0, unpacked.0);
-        assert_eq!(public_key.1, unpacked.1);
+        assert_field_eq(&public_key.0, &unpacked.0);
+        assert_field_eq(&public_key.1, &unpacked.1);
+    }
+
+    #[test]
+    fn test_ct_eq_packed() {
+        let packed = pack_public_key(&derive_public_key(b"secret").unwrap()).unwrap();
+        let other = pack_public_key(&derive_public_key(b"other").unwrap()).unwrap();
+        assert_eq!(ct_eq_packed(&packed, &packed.clone()), packed == packed.clone());
+        assert_eq!(ct_eq_packed(&packed, &other), packed == other);
+
+        let short = BigInt::from(1);
+        let long = BigInt::from(256);
+        assert!(ct_eq_packed(&short, &BigInt::from(1)));
+        assert_eq!(ct_eq_packed(&short, &long), short == long);
+        assert_eq!(ct_eq_packed(&short, &packed), short == packed);
+    }
+
+    #[test]
+    fn test_sign_message_legacy() {
+        let private_key = b"secret";
+        let message = [2u8; 32];
+        let legacy_key = mul_point_escalar(&BASE8, derive_secret_scalar_legacy(private_key));
+        let signature = sign_message_legacy(private_key, &message).unwrap();
+        assert!(verify_signature(&message, &signature, &legacy_key).unwrap());
+        assert!(!verify_signature(&message, &signature, &derive_public_key(private_key).unwrap()).unwrap());
+        assert!(sign_message_legacy(&[0u8; 32], &message).is_err());
+    }
+
+    #[test]
+    fn test_signature_scalar_is_reduced_mod_suborder() {
+        // S = r + 8 * hm * (s >> 3) is computed modulo the order of the subgroup B8 generates.
+        let private_key = b"secret";
+        let public_key = derive_public_key(private_key).unwrap();
+        let message = hash_input(b"message")[..31].to_vec();
+        let signature = sign_message(private_key, &message).unwrap();
+        assert!(signature.s < *SUBORDER);
+        assert!(verify_signature(&message, &signature, &public_key).unwrap());
     }
 
     #[test]
@@ -180,9 +1101,9 @@ mod tests {
         let packed = pack_signature(&signature).unwrap();
         assert_eq!(packed.len(), 64);
         let unpacked = unpack_signature(&packed).unwrap();
-        assert_eq!(signature.r8.0, unpacked.r8.0);
-        assert_eq!(signature.r8.1, unpacked.r8.1);
-        assert_eq!(signature.s, unpacked.s);
+        assert_field_eq(&signature.r8.0, &unpacked.r8.0);
+        assert_field_eq(&signature.r8.1, &unpacked.r8.1);
+        assert_field_eq(&signature.s, &unpacked.s);
     }
 
     // #[test]
@@ -193,6 +1114,25 @@ mod tests {
     //     assert!(result.is_err());
     // }
 
+    #[test]
+    fn test_unpack_signature_rejects_large_s() {
+        let signature = sign_message(b"secret", &[2u8; 32]).unwrap();
+        let mut packed = pack_signature(&signature).unwrap();
+        packed[32..].fill(0xff);
+        let err = unpack_signature(&packed).unwrap_err();
+        match err.downcast_ref::<SemaphoreError>() {
+            Some(SemaphoreError::InvalidScalar(msg)) => assert!(msg.contains(&"ff".repeat(32))),
+            other => panic!("unexpected error {:?}", other),
+        }
+
+        let suborder = le_bigint_to_bytes(&SUBORDER, Some(32)).unwrap();
+        packed[32..].copy_from_slice(&suborder);
+        assert!(unpack_signature(&packed).is_err());
+        let below = le_bigint_to_bytes(&(&*SUBORDER - 1), Some(32)).unwrap();
+        packed[32..].copy_from_slice(&below);
+        assert!(unpack_signature(&packed).is_ok());
+    }
+
     #[test]
     fn test_invalid_signature_length_unpack() {
         let short = vec![0u8; 63];
@@ -200,6 +1140,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_unpack_public_key_errors() {
+        let too_large = BigInt::from(1) << 256u32;
+        let err = unpack_public_key(&too_large).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SemaphoreError>(),
+            Some(&SemaphoreError::InvalidLength { expected: 32, actual: 33 })
+        );
+
+        // A y coordinate that fits in 32 bytes but that no point of the curve has.
+        let off_curve = (2u32..).map(BigInt::from).find(|y| unpack_point(y).is_none()).unwrap();
+        let err = unpack_public_key(&off_curve).unwrap_err();
+        assert!(matches!(err.downcast_ref::<SemaphoreError>(), Some(SemaphoreError::InvalidPoint(_))));
+
+        let err = unpack_public_key(&BigInt::from(-1)).unwrap_err();
+        assert!(matches!(err.downcast_ref::<SemaphoreError>(), Some(SemaphoreError::InvalidPoint(_))));
+
+        let public_key = derive_public_key(b"secret").unwrap();
+        assert_eq!(unpack_public_key(&pack_point(&public_key)).unwrap(), public_key);
+    }
+
     #[test]
     fn test_public_key_not_on_curve() {
         let invalid_point = (BigInt::from(0), BigInt::from(3));
@@ -216,6 +1177,20 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_all_zero_private_key_is_rejected() {
+        for key in [vec![], vec![0u8; 32], vec![0u8; 5]] {
+            let err = derive_secret_scalar(&key).unwrap_err();
+            assert_eq!(err.downcast_ref::<SemaphoreError>(), Some(&SemaphoreError::WeakKey));
+            assert!(derive_public_key(&key).is_err());
+            assert!(sign_message(&key, &[1u8; 32]).is_err());
+        }
+
+        let mut key = [0u8; 32];
+        key[17] = 1;
+        assert!(derive_public_key(&key).is_ok());
+    }
+
     #[test]
     fn test_random_private_key_derivation() {
         for _ in 0..10 {
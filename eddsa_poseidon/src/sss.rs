@@ -0,0 +1,191 @@
+use crate::error::SemaphoreError;
+use rand::Rng;
+
+/// Bytes before the share value: the threshold, then the share's `x` coordinate.
+const SHARE_HEADER_LEN: usize = 2;
+
+/// Splits a private key into `shares` Shamir shares, any `threshold` of which rebuild it with
+/// `recombine_private_key`. Each byte of the key is shared independently over GF(2^8) with a
+/// random polynomial of degree `threshold - 1`; fewer than `threshold` shares reveal nothing
+/// about the key.
+///
+/// A share is `[threshold, x, y_0, .., y_{n-1}]` for an `n`-byte key, with `x` in `1..=shares`.
+pub fn split_private_key(private_key: &[u8], threshold: u8, shares: u8) -> Result<Vec<Vec<u8>>, SemaphoreError> {
+    if threshold == 0 || threshold > shares {
+        return Err(SemaphoreError::InvalidParameter(format!(
+            "Threshold must be between 1 and the number of shares ({}), got {}",
+            shares, threshold
+        )));
+    }
+
+    let mut rng = rand::rng();
+    let mut result: Vec<Vec<u8>> = (1..=shares)
+        .map(|x| {
+            let mut share = Vec::with_capacity(SHARE_HEADER_LEN + private_key.len());
+            share.extend_from_slice(&[threshold, x]);
+            share
+        })
+        .collect();
+
+    let mut coefficients = vec![0u8; threshold as usize];
+    for &secret in private_key {
+        coefficients[0] = secret;
+        rng.fill(&mut coefficients[1..]);
+        for share in result.iter_mut() {
+            let x = share[1];
+            // Horner's rule, from the highest degree coefficient down.
+            let y = coefficients.iter().rev().fold(0, |acc, &c| gf_mul(acc, x) ^ c);
+            share.push(y);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Rebuilds a private key from shares produced by `split_private_key`. Fails if there are
+/// fewer shares than the threshold they were made with, or if the shares are inconsistent
+/// (different thresholds or lengths, or repeated `x` coordinates).
+pub fn recombine_private_key(shares: &[Vec<u8>]) -> Result<Vec<u8>, SemaphoreError> {
+    let first = shares
+        .first()
+        .ok_or_else(|| SemaphoreError::InvalidParameter("No shares given".to_string()))?;
+    if first.len() < SHARE_HEADER_LEN {
+        return Err(SemaphoreError::InvalidLength { expected: SHARE_HEADER_LEN, actual: first.len() });
+    }
+    let threshold = first[0] as usize;
+    if threshold == 0 || shares.len() < threshold {
+        return Err(SemaphoreError::InvalidParameter(format!(
+            "Need {} shares, got {}",
+            threshold,
+            shares.len()
+        )));
+    }
+
+    let shares = &shares[..threshold];
+    let mut seen = [false; 256];
+    for share in shares {
+        if share.len() != first.len() {
+            return Err(SemaphoreError::InvalidLength { expected: first.len(), actual: share.len() });
+        }
+        if share[0] != first[0] {
+            return Err(SemaphoreError::InvalidParameter("Shares have different thresholds".to_string()));
+        }
+        let x = share[1] as usize;
+        if x == 0 || seen[x] {
+            return Err(SemaphoreError::InvalidParameter(format!("Invalid or repeated share index {}", x)));
+        }
+        seen[x] = true;
+    }
+
+    // Lagrange basis polynomials evaluated at 0: l_i = prod_{j != i} x_j / (x_j - x_i),
+    // where subtraction in GF(2^8) is XOR.
+    let basis: Vec<u8> = shares
+        .iter()
+        .map(|share_i| {
+            shares
+                .iter()
+                .filter(|share_j| share_j[1] != share_i[1])
+                .fold(1, |acc, share_j| gf_mul(acc, gf_div(share_j[1], share_j[1] ^ share_i[1])))
+        })
+        .collect();
+
+    Ok((SHARE_HEADER_LEN..first.len())
+        .map(|k| {
+            shares
+                .iter()
+                .zip(&basis)
+                .fold(0, |acc, (share, &l)| acc ^ gf_mul(share[k], l))
+        })
+        .collect())
+}
+
+/// Multiplication in GF(2^8) modulo the AES polynomial `x^8 + x^4 + x^3 + x + 1`.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(2^8); `b` must be non-zero. The inverse of `b` is `b^254`.
+fn gf_div(a: u8, b: u8) -> u8 {
+    let mut inverse = 1;
+    let mut power = b;
+    let mut exp = 254u8;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            inverse = gf_mul(inverse, power);
+        }
+        power = gf_mul(power, power);
+        exp >>= 1;
+    }
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf_arithmetic() {
+        // Known AES field product: {57} * {83} = {c1}.
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        for b in 1..=255u8 {
+            assert_eq!(gf_mul(gf_div(1, b), b), 1);
+        }
+    }
+
+    #[test]
+    fn test_recombine_from_threshold_shares() {
+        let private_key: Vec<u8> = (0..32).collect();
+        let shares = split_private_key(&private_key, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.len() == 2 + 32));
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1], [2, 3, 4]] {
+            let chosen: Vec<Vec<u8>> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(recombine_private_key(&chosen).unwrap(), private_key);
+        }
+        assert_eq!(recombine_private_key(&shares).unwrap(), private_key);
+
+        let single = split_private_key(b"secret", 1, 1).unwrap();
+        assert_eq!(recombine_private_key(&single).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn test_recombine_fails_with_fewer_shares() {
+        let shares = split_private_key(b"secret", 3, 5).unwrap();
+        assert!(matches!(
+            recombine_private_key(&shares[..2]),
+            Err(SemaphoreError::InvalidParameter(_))
+        ));
+        assert!(recombine_private_key(&[]).is_err());
+
+        let repeated = vec![shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(recombine_private_key(&repeated).is_err());
+        let truncated = vec![shares[0].clone(), shares[1].clone(), shares[2][..4].to_vec()];
+        assert!(matches!(
+            recombine_private_key(&truncated),
+            Err(SemaphoreError::InvalidLength { .. })
+        ));
+    }
+
+    #[test]
+    fn test_split_validates_threshold() {
+        assert!(matches!(
+            split_private_key(b"secret", 4, 3),
+            Err(SemaphoreError::InvalidParameter(_))
+        ));
+        assert!(split_private_key(b"secret", 0, 3).is_err());
+        assert!(split_private_key(b"secret", 3, 3).is_ok());
+    }
+}
@@ -0,0 +1,187 @@
+//! Verification of signature records read from a byte stream.
+//!
+//! A stream is a sequence of records, each laid out as:
+//!
+//! | bytes | content                                             |
+//! |-------|-----------------------------------------------------|
+//! | 4     | message length `n`, little-endian `u32`, at most 32 |
+//! | `n`   | message                                             |
+//! | 64    | packed signature, as `pack_signature`               |
+//! | 32    | packed public key, little-endian `pack_point`       |
+//!
+//! The stream ends cleanly when it is exhausted at a record boundary.
+
+use crate::error::SemaphoreError;
+use crate::util_functions::Signature;
+use crate::{pack_signature, verify_signature};
+use baby_jubjub::{Point, pack_point, unpack_point};
+use std::io::{self, Read, Write};
+use utils::conversions::{le_bigint_to_bytes, le_bytes_to_bigint};
+
+/// Longest message a record may hold; longer messages are never valid field elements.
+pub const MAX_RECORD_MESSAGE_LEN: usize = 32;
+
+/// Appends one record to `writer` in the framing described in the module documentation.
+pub fn write_record<W: Write>(writer: &mut W, message: &[u8], signature: &Signature, public_key: &Point) -> Result<(), SemaphoreError> {
+    if message.len() > MAX_RECORD_MESSAGE_LEN {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "Messages are at most {} bytes, got {}",
+            MAX_RECORD_MESSAGE_LEN,
+            message.len()
+        )));
+    }
+    let signature = pack_signature(signature).map_err(|e| SemaphoreError::InvalidSignature(e.to_string()))?;
+    let public_key = le_bigint_to_bytes(&pack_point(public_key), Some(32)).map_err(SemaphoreError::InvalidPublicKey)?;
+
+    let mut record = Vec::with_capacity(4 + message.len() + 64 + 32);
+    record.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    record.extend_from_slice(message);
+    record.extend_from_slice(&signature);
+    record.extend_from_slice(&public_key);
+    writer.write_all(&record).map_err(io_error)
+}
+
+/// Lazily reads records from `reader` and verifies each one, yielding one result per record.
+///
+/// A record whose signature or public key does not unpack, or whose message is not a field
+/// element, yields an error and the stream continues with the next record. A read error, a
+/// truncated record or an oversized message length yields an error and ends the stream, since
+/// the framing can no longer be trusted.
+pub fn verify_stream<R: Read>(mut reader: R) -> impl Iterator<Item = Result<bool, SemaphoreError>> {
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        match read_record(&mut reader) {
+            Ok(Some((message, signature, public_key))) => Some(verify_record(&message, &signature, &public_key)),
+            Ok(None) => {
+                done = true;
+                None
+            }
+            Err(e) => {
+                done = true;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+type RawRecord = (Vec<u8>, [u8; 64], [u8; 32]);
+
+/// Reads the next record, or `None` at a clean end of stream.
+fn read_record<R: Read>(reader: &mut R) -> Result<Option<RawRecord>, SemaphoreError> {
+    let mut length = [0u8; 4];
+    match read_full(reader, &mut length)? {
+        0 => return Ok(None),
+        4 => {}
+        actual => return Err(SemaphoreError::InvalidLength { expected: 4, actual }),
+    }
+    let length = u32::from_le_bytes(length) as usize;
+    if length > MAX_RECORD_MESSAGE_LEN {
+        return Err(SemaphoreError::InvalidMessage(format!(
+            "Record message length {} exceeds {} bytes",
+            length, MAX_RECORD_MESSAGE_LEN
+        )));
+    }
+
+    let mut message = vec![0u8; length];
+    let mut signature = [0u8; 64];
+    let mut public_key = [0u8; 32];
+    read_exact(reader, &mut message)?;
+    read_exact(reader, &mut signature)?;
+    read_exact(reader, &mut public_key)?;
+    Ok(Some((message, signature, public_key)))
+}
+
+fn verify_record(message: &[u8], signature: &[u8; 64], public_key: &[u8; 32]) -> Result<bool, SemaphoreError> {
+    let signature = Signature::try_from(&signature[..])?;
+    let public_key = unpack_point(&le_bytes_to_bigint(public_key))
+        .ok_or_else(|| SemaphoreError::InvalidPublicKey("Packed public key does not unpack".to_string()))?;
+    verify_signature(message, &signature, &public_key).map_err(|e| SemaphoreError::InvalidMessage(e.to_string()))
+}
+
+/// Reads until `buf` is full or the stream ends, returning the number of bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, SemaphoreError> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+    Ok(read)
+}
+
+/// Fills `buf`, failing with `InvalidLength` if the stream ends first.
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), SemaphoreError> {
+    let read = read_full(reader, buf)?;
+    if read != buf.len() {
+        return Err(SemaphoreError::InvalidLength { expected: buf.len(), actual: read });
+    }
+    Ok(())
+}
+
+fn io_error(e: io::Error) -> SemaphoreError {
+    SemaphoreError::InvalidParameter(format!("Stream I/O error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{derive_public_key, sign_message};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_verify_stream_round_trip() {
+        let mut stream = Vec::new();
+        let mut expected = Vec::new();
+        for i in 1..=3u8 {
+            let private_key = [i; 32];
+            let message = [i; 8];
+            let signature = sign_message(&private_key, &message).unwrap();
+            let public_key = derive_public_key(&private_key).unwrap();
+            write_record(&mut stream, &message, &signature, &public_key).unwrap();
+            expected.push(true);
+        }
+        // A valid signature presented with another signer's key.
+        let signature = sign_message(&[1u8; 32], &[1u8; 8]).unwrap();
+        write_record(&mut stream, &[1u8; 8], &signature, &derive_public_key(&[2u8; 32]).unwrap()).unwrap();
+        expected.push(false);
+
+        let results: Vec<bool> = verify_stream(Cursor::new(&stream)).map(Result::unwrap).collect();
+        assert_eq!(results, expected);
+        assert_eq!(verify_stream(Cursor::new(Vec::new())).count(), 0);
+    }
+
+    #[test]
+    fn test_verify_stream_errors() {
+        let signature = sign_message(b"secret", &[2u8; 32]).unwrap();
+        let public_key = derive_public_key(b"secret").unwrap();
+        let mut record = Vec::new();
+        write_record(&mut record, &[2u8; 32], &signature, &public_key).unwrap();
+
+        // A bad signature only fails its own record.
+        let mut bad_signature = record.clone();
+        bad_signature[4 + 32 + 32..4 + 32 + 64].copy_from_slice(&[0xff; 32]);
+        let stream = [bad_signature, record.clone()].concat();
+        let results: Vec<_> = verify_stream(Cursor::new(stream)).collect();
+        assert!(matches!(results[0], Err(SemaphoreError::InvalidScalar(_))));
+        assert_eq!(results[1], Ok(true));
+
+        // A truncated record ends the stream.
+        let stream = [record.clone(), record[..50].to_vec()].concat();
+        let results: Vec<_> = verify_stream(Cursor::new(stream)).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], Ok(true));
+        assert!(matches!(results[1], Err(SemaphoreError::InvalidLength { .. })));
+
+        let mut oversized = record;
+        oversized[..4].copy_from_slice(&33u32.to_le_bytes());
+        let results: Vec<_> = verify_stream(Cursor::new(oversized)).collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(SemaphoreError::InvalidMessage(_))));
+    }
+}
@@ -1,5 +1,10 @@
 use num_bigint::BigInt;
-use baby_jubjub::Point;
+use baby_jubjub::{Point, R, SUBORDER, in_curve, in_subgroup, unpack_point};
+use serde_json::{json, Value};
+use std::fmt;
+use crate::error::SemaphoreError;
+use crate::{pack_signature, unpack_signature};
+use utils::conversions::le_bytes_to_bigint;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Signature {
@@ -7,15 +12,355 @@ pub struct Signature {
     pub s: BigInt,
 }
 
+impl Signature {
+    /// Serializes the signature as `{"R8": ["x", "y"], "S": "s"}` with decimal strings,
+    /// following the `@zk-kit/eddsa-poseidon` JSON convention.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "R8": [self.r8.0.to_string(), self.r8.1.to_string()],
+            "S": self.s.to_string(),
+        })
+    }
+
+    /// Parses a signature from the JSON produced by `Signature::to_json`,
+    /// checking that coordinates are field elements, `R8` is on the curve and `S < SUBORDER`.
+    pub fn from_json(json: &Value) -> Result<Self, SemaphoreError> {
+        let r8 = json
+            .get("R8")
+            .and_then(Value::as_array)
+            .filter(|r8| r8.len() == 2)
+            .ok_or_else(|| SemaphoreError::InvalidSignature("'R8' must be an array of two coordinates".to_string()))?;
+        let x = parse_decimal(&r8[0], "R8[0]")?;
+        let y = parse_decimal(&r8[1], "R8[1]")?;
+        let s = parse_decimal(json.get("S").unwrap_or(&Value::Null), "S")?;
+
+        if x >= *R || y >= *R {
+            return Err(SemaphoreError::InvalidSignature("'R8' coordinates must be field elements".to_string()));
+        }
+        let r8 = (x, y);
+        if !in_curve(&r8) {
+            return Err(SemaphoreError::InvalidSignature("'R8' is not on the curve".to_string()));
+        }
+        if s >= *SUBORDER {
+            return Err(SemaphoreError::InvalidSignature("'S' must be lower than the subgroup order".to_string()));
+        }
+
+        Ok(Signature { r8, s })
+    }
+
+    /// Parses an untrusted 64-byte packed signature, checking everything up front: the length,
+    /// that `R8` unpacks to a point of the prime-order subgroup and that `S < SUBORDER`.
+    /// Each failure has its own error: `InvalidLength`, `InvalidPoint` or `InvalidScalar`.
+    pub fn from_packed_validated(packed: &[u8]) -> Result<Self, SemaphoreError> {
+        if packed.len() != 64 {
+            return Err(SemaphoreError::InvalidLength { expected: 64, actual: packed.len() });
+        }
+
+        let r8 = unpack_point(&le_bytes_to_bigint(&packed[..32]))
+            .ok_or_else(|| SemaphoreError::InvalidPoint("'R8' does not unpack to a curve point".to_string()))?;
+        if !in_subgroup(&r8) {
+            return Err(SemaphoreError::InvalidPoint("'R8' is not in the prime-order subgroup".to_string()));
+        }
+
+        let s = le_bytes_to_bigint(&packed[32..]);
+        if s >= *SUBORDER {
+            return Err(SemaphoreError::InvalidScalar(format!("'S' = {} is not lower than the subgroup order", s)));
+        }
+
+        Ok(Signature { r8, s })
+    }
+
+    /// Builds a signature from a 32-byte packed `R8` and a 32-byte little-endian `S` given
+    /// separately, with the same checks and errors as `Signature::from_packed_validated`.
+    pub fn from_components(r8_packed: &[u8], s_bytes: &[u8]) -> Result<Self, SemaphoreError> {
+        for component in [r8_packed, s_bytes] {
+            if component.len() != 32 {
+                return Err(SemaphoreError::InvalidLength { expected: 32, actual: component.len() });
+            }
+        }
+        Signature::from_packed_validated(&[r8_packed, s_bytes].concat())
+    }
+
+    /// Splits the packed signature into its packed `R8` and `S` halves.
+    ///
+    /// # Panics
+    /// If the signature cannot be packed (off-curve `R8` or `S` out of range).
+    pub fn to_components(&self) -> ([u8; 32], [u8; 32]) {
+        let SignatureBytes(bytes) = SignatureBytes::from(self);
+        let mut r8 = [0u8; 32];
+        let mut s = [0u8; 32];
+        r8.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+        (r8, s)
+    }
+}
+
+fn parse_decimal(value: &Value, field: &str) -> Result<BigInt, SemaphoreError> {
+    value
+        .as_str()
+        .and_then(|s| s.parse::<BigInt>().ok())
+        .filter(|n| n.sign() != num_bigint::Sign::Minus)
+        .ok_or_else(|| SemaphoreError::InvalidSignature(format!("'{}' must be a non-negative decimal string", field)))
+}
+
+/// A packed signature stored on the stack: 32 bytes of packed `R8` followed by 32 bytes of `S`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureBytes(pub [u8; 64]);
+
+impl From<&Signature> for SignatureBytes {
+    /// # Panics
+    /// If the signature cannot be packed (off-curve `R8` or `S` out of range).
+    fn from(signature: &Signature) -> Self {
+        let packed = pack_signature(signature).expect("Signature cannot be packed");
+        let mut bytes = [0u8; 64];
+        bytes.copy_from_slice(&packed);
+        SignatureBytes(bytes)
+    }
+}
+
+impl TryFrom<SignatureBytes> for Signature {
+    type Error = SemaphoreError;
+
+    fn try_from(bytes: SignatureBytes) -> Result<Self, Self::Error> {
+        unpack_signature(&bytes.0).map_err(|e| SemaphoreError::InvalidParameter(e.to_string()))
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = SemaphoreError;
+
+    /// Unpacks a 64-byte packed signature with `unpack_signature`, rejecting a wrong length
+    /// (`InvalidLength`), an `R8` that does not unpack to a curve point (`InvalidPoint`) and
+    /// `S >= SUBORDER` (`InvalidScalar`). Unlike `Signature::from_packed_validated`, `R8` may
+    /// lie outside the prime-order subgroup.
+    fn try_from(packed: &[u8]) -> Result<Self, Self::Error> {
+        if packed.len() != 64 {
+            return Err(SemaphoreError::InvalidLength { expected: 64, actual: packed.len() });
+        }
+        unpack_signature(packed).map_err(|e| match e.downcast::<SemaphoreError>() {
+            Ok(e) => *e,
+            Err(e) => SemaphoreError::InvalidPoint(e.to_string()),
+        })
+    }
+}
+
+impl From<&Signature> for [u8; 64] {
+    /// # Panics
+    /// If the signature cannot be packed (off-curve `R8` or `S` out of range).
+    fn from(signature: &Signature) -> Self {
+        SignatureBytes::from(signature).0
+    }
+}
+
+impl AsRef<[u8]> for SignatureBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SignatureBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+/// Clamps a 32-byte little-endian scalar in place, as in Ed25519:
+/// clears the lowest 3 bits (cofactor), clears the highest bit and sets the second-highest.
+pub fn clamp_scalar_bytes(buf: &mut [u8]) {
+    buf[0] &= 0xf8;
+    buf[31] &= 0x7f;
+    buf[31] |= 0x40;
+}
+
 pub fn prune_buffer(mut buff: Vec<u8>) -> Vec<u8> {
-    buff[0] &= 0xf8;
-    buff[31] &= 0x7f;
-    buff[31] |= 0x40;
+    clamp_scalar_bytes(&mut buff);
     buff
 }
 
+/// Hashes a message with BLAKE-512.
 pub fn hash_input(message: &[u8]) -> Vec<u8> {
-    let mut hash = [0; 64];
-    blake::hash(512, message, &mut hash).unwrap();
-    hash.to_vec()
+    hash_input_sized(message, 512).expect("BLAKE supports 512-bit digests")
+}
+
+/// Hashes a message with BLAKE using a digest of `bits` bits (224, 256, 384 or 512).
+pub fn hash_input_sized(message: &[u8], bits: usize) -> Result<Vec<u8>, SemaphoreError> {
+    if !bits.is_multiple_of(8) || ![224, 256, 384, 512].contains(&bits) {
+        return Err(SemaphoreError::InvalidParameter(format!("Unsupported BLAKE digest size: {} bits", bits)));
+    }
+    let mut hash = vec![0; bits / 8];
+    blake::hash(bits as i32, message, &mut hash)
+        .map_err(|e| SemaphoreError::InvalidParameter(format!("{:?}", e)))?;
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_scalar_bytes() {
+        let mut buf = [0xffu8; 32];
+        clamp_scalar_bytes(&mut buf);
+        assert_eq!(buf[0], 0xf8);
+        assert_eq!(buf[31], 0x7f);
+        assert!(buf[1..31].iter().all(|&b| b == 0xff));
+
+        let mut buf = [0u8; 32];
+        clamp_scalar_bytes(&mut buf);
+        assert_eq!(buf[0], 0x00);
+        assert_eq!(buf[31], 0x40);
+        assert!(buf[1..31].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_signature_try_from_slice() {
+        let signature = crate::sign_message(b"secret", &[2u8; 32]).unwrap();
+        let packed: [u8; 64] = (&signature).into();
+        assert_eq!(packed.to_vec(), pack_signature(&signature).unwrap());
+        let parsed: Signature = packed.as_slice().try_into().unwrap();
+        assert_eq!(parsed, signature);
+
+        assert_eq!(
+            Signature::try_from(&packed[..63]),
+            Err(SemaphoreError::InvalidLength { expected: 64, actual: 63 })
+        );
+
+        // Find a y coordinate with no matching x, so R8 cannot be unpacked.
+        let y = (2u32..)
+            .map(BigInt::from)
+            .find(|y| unpack_point(y).is_none())
+            .unwrap();
+        let mut off_curve = packed;
+        off_curve[..32].copy_from_slice(&utils::conversions::le_bigint_to_bytes(&y, Some(32)).unwrap());
+        assert!(matches!(Signature::try_from(&off_curve[..]), Err(SemaphoreError::InvalidPoint(_))));
+
+        let mut large_s = packed;
+        large_s[32..].copy_from_slice(&utils::conversions::le_bigint_to_bytes(&SUBORDER, Some(32)).unwrap());
+        assert!(matches!(Signature::try_from(&large_s[..]), Err(SemaphoreError::InvalidScalar(_))));
+    }
+
+    #[test]
+    fn test_signature_json_roundtrip() {
+        let signature = crate::sign_message(b"secret", &[2u8; 32]).unwrap();
+        let json = signature.to_json();
+        assert_eq!(json["R8"][0], signature.r8.0.to_string());
+        assert_eq!(json["S"], signature.s.to_string());
+        assert_eq!(Signature::from_json(&json).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_signature_from_json_rejects_invalid_input() {
+        let signature = crate::sign_message(b"secret", &[2u8; 32]).unwrap();
+
+        let mut off_curve = signature.to_json();
+        off_curve["R8"][1] = json!("3");
+        assert!(matches!(Signature::from_json(&off_curve), Err(SemaphoreError::InvalidSignature(_))));
+
+        let mut large_s = signature.to_json();
+        large_s["S"] = json!(SUBORDER.to_string());
+        assert!(Signature::from_json(&large_s).is_err());
+
+        let mut number_s = signature.to_json();
+        number_s["S"] = json!(1);
+        assert!(Signature::from_json(&number_s).is_err());
+
+        assert!(Signature::from_json(&json!({ "R8": ["1"], "S": "1" })).is_err());
+    }
+
+    #[test]
+    fn test_signature_bytes_roundtrip() {
+        let message = [2u8; 32];
+        let signature = crate::sign_message(b"secret", &message).unwrap();
+        let bytes = SignatureBytes::from(&signature);
+
+        assert_eq!(bytes.as_ref(), pack_signature(&signature).unwrap().as_slice());
+        assert_eq!(bytes.to_string(), hex::encode(bytes.0));
+        assert_eq!(bytes.to_string().len(), 128);
+
+        let unpacked: Signature = bytes.try_into().unwrap();
+        assert_eq!(unpacked, signature);
+    }
+
+    #[test]
+    fn test_from_packed_validated() {
+        let signature = crate::sign_message(b"secret", &[2u8; 32]).unwrap();
+        let packed = pack_signature(&signature).unwrap();
+        assert_eq!(Signature::from_packed_validated(&packed).unwrap(), signature);
+
+        assert_eq!(
+            Signature::from_packed_validated(&packed[..63]),
+            Err(SemaphoreError::InvalidLength { expected: 64, actual: 63 })
+        );
+
+        let mut not_a_point = packed.clone();
+        not_a_point[31] |= 0x40;
+        assert!(matches!(
+            Signature::from_packed_validated(&not_a_point),
+            Err(SemaphoreError::InvalidPoint(_))
+        ));
+
+        // (0, -1) is on the curve but has order 2.
+        let mut low_order = packed.clone();
+        let torsion = baby_jubjub::pack_point(&(BigInt::from(0), &*R - 1));
+        low_order[..32].copy_from_slice(&utils::conversions::le_bigint_to_bytes(&torsion, Some(32)).unwrap());
+        assert!(matches!(
+            Signature::from_packed_validated(&low_order),
+            Err(SemaphoreError::InvalidPoint(_))
+        ));
+
+        let mut big_s = packed.clone();
+        big_s[32..].copy_from_slice(&utils::conversions::le_bigint_to_bytes(&SUBORDER, Some(32)).unwrap());
+        assert!(matches!(
+            Signature::from_packed_validated(&big_s),
+            Err(SemaphoreError::InvalidScalar(_))
+        ));
+    }
+
+    #[test]
+    fn test_signature_components_round_trip() {
+        let signature = crate::sign_message(b"secret", &[2u8; 32]).unwrap();
+        let (r8, s) = signature.to_components();
+        assert_eq!([r8, s].concat(), pack_signature(&signature).unwrap());
+        assert_eq!(Signature::from_components(&r8, &s).unwrap(), signature);
+
+        assert_eq!(
+            Signature::from_components(&r8[..31], &s),
+            Err(SemaphoreError::InvalidLength { expected: 32, actual: 31 })
+        );
+        assert_eq!(
+            Signature::from_components(&r8, &s[..31]),
+            Err(SemaphoreError::InvalidLength { expected: 32, actual: 31 })
+        );
+        assert!(matches!(
+            Signature::from_components(&r8, &[0xff; 32]),
+            Err(SemaphoreError::InvalidScalar(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_input_sized() {
+        let digest_256 = hash_input_sized(b"", 256).unwrap();
+        assert_eq!(
+            hex::encode(&digest_256),
+            "716f6e863f744b9ac22c97ec7b76ea5f5908bc5b2f67c61510bfc4751384ea7a"
+        );
+
+        let digest_512 = hash_input_sized(b"secret", 512).unwrap();
+        assert_eq!(digest_512.len(), 64);
+        assert_eq!(digest_512, hash_input(b"secret"));
+    }
+
+    #[test]
+    fn test_hash_input_sized_rejects_unsupported_sizes() {
+        assert!(matches!(hash_input_sized(b"", 255), Err(SemaphoreError::InvalidParameter(_))));
+        assert!(matches!(hash_input_sized(b"", 128), Err(SemaphoreError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_prune_buffer_matches_clamp() {
+        let input: Vec<u8> = (0..32).map(|i| i * 7 + 3).collect();
+        let mut clamped = input.clone();
+        clamp_scalar_bytes(&mut clamped);
+        assert_eq!(prune_buffer(input), clamped);
+    }
 }
@@ -0,0 +1,364 @@
+use crate::error::SemaphoreError;
+use crate::util_functions::Signature;
+use crate::{canonicalize_message, challenge_scalar, compute_challenge};
+use baby_jubjub::{BASE8, Fr, Point, add_point, add_point_assign, in_curve, in_subgroup, is_identity, mul_point_escalar, pack_point};
+use num_bigint::BigInt;
+use std::collections::{BTreeMap, HashMap};
+use utils::conversions::le_bytes_to_bigint;
+
+/// Verifies signatures while caching public keys that passed the curve and subgroup checks,
+/// so repeated keys skip them. Keys are cached by packed form, at most `capacity` of them;
+/// the least recently used key is evicted first. Invalid keys are never cached.
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    capacity: usize,
+    tick: u64,
+    keys: HashMap<BigInt, (Point, u64)>,
+    recency: BTreeMap<u64, BigInt>,
+}
+
+impl Verifier {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            keys: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// Returns true if `public_key` is currently cached as validated.
+    pub fn is_cached(&self, public_key: &Point) -> bool {
+        self.keys
+            .get(&pack_point(public_key))
+            .is_some_and(|(cached, _)| cached == public_key)
+    }
+
+    /// Same result as `verify_signature`, with the checks in the same order: an invalid key or
+    /// `R8` gives `Ok(false)` before a message that does not fit in 32 bytes gives an error.
+    /// The one difference is that the identity is rejected as public key.
+    pub fn verify(&mut self, message: &[u8], signature: &Signature, public_key: &Point) -> Result<bool, SemaphoreError> {
+        if !self.check_public_key(public_key) || !in_curve(&signature.r8) {
+            return Ok(false);
+        }
+        let message = le_bytes_to_bigint(&canonicalize_message(message)?);
+
+        let hm = compute_challenge(&signature.r8, public_key, &message);
+        let lhs = mul_point_escalar(&BASE8, signature.s.clone());
+        let rhs = add_point(&signature.r8, &mul_point_escalar(public_key, challenge_scalar(&hm)));
+        Ok(lhs == rhs)
+    }
+
+    /// Validates `public_key`, or finds it in the cache, marking it as most recently used.
+    fn check_public_key(&mut self, public_key: &Point) -> bool {
+        self.tick += 1;
+        let packed = pack_point(public_key);
+        // The packed form alone does not identify a point that is not on the curve, so the
+        // cached coordinates must match too.
+        if let Some((cached, last_used)) = self.keys.get_mut(&packed)
+            && cached == public_key
+        {
+            self.recency.remove(last_used);
+            *last_used = self.tick;
+            self.recency.insert(self.tick, packed);
+            return true;
+        }
+
+        if !in_subgroup(public_key) || is_identity(public_key) {
+            return false;
+        }
+        if self.capacity == 0 {
+            return true;
+        }
+        if self.keys.len() >= self.capacity
+            && let Some((_, evicted)) = self.recency.pop_first()
+        {
+            self.keys.remove(&evicted);
+        }
+        if let Some((_, previous)) = self.keys.insert(packed.clone(), (public_key.clone(), self.tick)) {
+            self.recency.remove(&previous);
+        }
+        self.recency.insert(self.tick, packed);
+        true
+    }
+}
+
+/// Bits of the challenge consumed per table row.
+const WINDOW_BITS: usize = 4;
+/// Rows per key; challenges are Poseidon outputs, which are below `R < 2^256`.
+const WINDOWS: usize = 256 / WINDOW_BITS;
+
+/// Verifies signatures from a fixed set of public keys using precomputed multiples of each key.
+///
+/// For every key `A`, row `i` of its table holds `j * 16^i * 8A` for `j` in `0..16`, so the
+/// `8*hm*A` term of the verification equation costs at most 64 point additions and no doublings.
+/// Each key takes 1024 points of memory.
+#[derive(Debug, Clone)]
+pub struct PublicKeyTable {
+    keys: Vec<Point>,
+    tables: Vec<Vec<Point>>,
+}
+
+impl PublicKeyTable {
+    /// Precomputes tables for `public_keys`, which keep their order for `verify_with_table`.
+    /// Fails if a key is not in the prime-order subgroup, which `verify_signature` requires.
+    pub fn new(public_keys: &[Point]) -> Result<Self, SemaphoreError> {
+        if let Some(index) = public_keys.iter().position(|key| !in_subgroup(key)) {
+            return Err(SemaphoreError::InvalidPublicKey(format!(
+                "key {} is not in the prime-order subgroup",
+                index
+            )));
+        }
+
+        Ok(Self {
+            keys: public_keys.to_vec(),
+            tables: public_keys.iter().map(|key| precompute(&mul_point_escalar(key, BigInt::from(8)))).collect(),
+        })
+    }
+
+    /// Number of keys in the table.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Same result as `verify_signature` with the key at `key_index`; false if there is no
+    /// such key or the message does not fit in 32 bytes. As with `verify_signature`, a message
+    /// of `R` or more is not rejected: the challenge hash reduces it modulo `R`.
+    pub fn verify_with_table(&self, key_index: usize, message: &[u8], signature: &Signature) -> bool {
+        let (Some(public_key), Some(table)) = (self.keys.get(key_index), self.tables.get(key_index)) else {
+            return false;
+        };
+        let Ok(message) = canonicalize_message(message) else {
+            return false;
+        };
+        if !in_curve(&signature.r8) {
+            return false;
+        }
+
+        let hm = compute_challenge(&signature.r8, public_key, &le_bytes_to_bigint(&message));
+        let lhs = mul_point_escalar(&BASE8, signature.s.clone());
+        let rhs = add_point(&signature.r8, &mul_with_table(table, &hm));
+        Fr.eq(&lhs.0, &rhs.0) && Fr.eq(&lhs.1, &rhs.1)
+    }
+}
+
+/// Builds the `WINDOWS * 16` multiples of `base` described on `PublicKeyTable`.
+fn precompute(base: &Point) -> Vec<Point> {
+    let mut table = Vec::with_capacity(WINDOWS << WINDOW_BITS);
+    let mut row_base = base.clone();
+    for _ in 0..WINDOWS {
+        let mut multiple = (Fr.zero.clone(), Fr.one.clone());
+        for _ in 0..1 << WINDOW_BITS {
+            table.push(multiple.clone());
+            add_point_assign(&mut multiple, &row_base);
+        }
+        // After the loop `multiple` is 16 * row_base, the base of the next row.
+        row_base = multiple;
+    }
+    table
+}
+
+/// Multiplies the table's base point by a scalar in `[0, 2^256)`.
+fn mul_with_table(table: &[Point], scalar: &BigInt) -> Point {
+    let (_, bytes) = scalar.to_bytes_le();
+    debug_assert!(bytes.len() * 8 <= WINDOWS * WINDOW_BITS, "scalar does not fit the table");
+
+    let mut result = (Fr.zero.clone(), Fr.one.clone());
+    for (i, byte) in bytes.iter().enumerate().take(WINDOWS / 2) {
+        for (row, digit) in [(2 * i, byte & 0x0f), (2 * i + 1, byte >> 4)] {
+            if digit != 0 {
+                add_point_assign(&mut result, &table[(row << WINDOW_BITS) + digit as usize]);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{derive_public_key, sign_message, verify_signature};
+    use utils::conversions::le_bigint_to_bytes;
+    use baby_jubjub::Fr;
+
+    #[test]
+    fn test_cached_key_still_verifies() {
+        let mut verifier = Verifier::new(2);
+        let message = [2u8; 32];
+        let public_key = derive_public_key(b"secret").unwrap();
+        let signature = sign_message(b"secret", &message).unwrap();
+
+        assert!(!verifier.is_cached(&public_key));
+        assert!(verifier.verify(&message, &signature, &public_key).unwrap());
+        assert!(verifier.is_cached(&public_key));
+        assert!(verifier.verify(&message, &signature, &public_key).unwrap());
+        assert!(!verifier.verify(&[3u8; 32], &signature, &public_key).unwrap());
+        assert_eq!(
+            verifier.verify(&message, &signature, &public_key).unwrap(),
+            verify_signature(&message, &signature, &public_key).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_key_is_never_cached() {
+        let mut verifier = Verifier::new(2);
+        let message = [2u8; 32];
+        let public_key = derive_public_key(b"secret").unwrap();
+        let signature = sign_message(b"secret", &message).unwrap();
+
+        let mut off_curve = public_key.clone();
+        off_curve.1 += 1;
+        assert!(!verifier.verify(&message, &signature, &off_curve).unwrap());
+        assert!(!verifier.is_cached(&off_curve));
+
+        // Caching the real key must not let an off-curve point with the same packing through.
+        assert!(verifier.verify(&message, &signature, &public_key).unwrap());
+        let forged = (Fr.add(&public_key.0, &Fr.one), public_key.1.clone());
+        if pack_point(&forged) == pack_point(&public_key) {
+            assert!(!verifier.is_cached(&forged));
+        }
+        assert!(!verifier.verify(&message, &signature, &forged).unwrap());
+        assert!(!verifier.is_cached(&forged));
+
+        let low_order = (BigInt::from(0), &*baby_jubjub::R - 1);
+        assert!(!verifier.verify(&message, &signature, &low_order).unwrap());
+        assert!(!verifier.is_cached(&low_order));
+    }
+
+    #[test]
+    fn test_verify_matches_verify_signature_on_invalid_inputs() {
+        let mut verifier = Verifier::new(2);
+        let message = [2u8; 32];
+        let public_key = derive_public_key(b"secret").unwrap();
+        let signature = sign_message(b"secret", &message).unwrap();
+
+        let mut off_curve_key = public_key.clone();
+        off_curve_key.1 += 1;
+        let low_order_key = (BigInt::from(0), &*baby_jubjub::R - 1);
+        let mut off_curve_r8 = signature.clone();
+        off_curve_r8.r8.1 += 1;
+        let mut wrong_s = signature.clone();
+        wrong_s.s += 1;
+
+        let oversized = [0xffu8; 33];
+        let cases: [(&[u8], &Signature, &Point); 9] = [
+            (&message, &signature, &public_key),
+            (&[3u8; 32], &signature, &public_key),
+            (&message, &wrong_s, &public_key),
+            (&message, &off_curve_r8, &public_key),
+            (&message, &signature, &off_curve_key),
+            (&message, &signature, &low_order_key),
+            (&oversized, &signature, &public_key),
+            (&oversized, &signature, &off_curve_key),
+            (&oversized, &off_curve_r8, &public_key),
+        ];
+        for (message, signature, public_key) in cases {
+            let expected = verify_signature(message, signature, public_key).ok();
+            assert_eq!(verifier.verify(message, signature, public_key).ok(), expected);
+        }
+
+        // The identity is the documented difference: S = 0 with R8 the identity verifies under it.
+        let identity = (BigInt::from(0), BigInt::from(1));
+        let trivial = Signature { r8: identity.clone(), s: BigInt::from(0) };
+        assert!(verify_signature(&message, &trivial, &identity).unwrap());
+        assert!(!verifier.verify(&message, &trivial, &identity).unwrap());
+    }
+
+    #[test]
+    fn test_cache_is_bounded_lru() {
+        let mut verifier = Verifier::new(2);
+        let message = [2u8; 32];
+        let keys: Vec<(Point, Signature)> = (1..=3u8)
+            .map(|i| (derive_public_key(&[i; 32]).unwrap(), sign_message(&[i; 32], &message).unwrap()))
+            .collect();
+
+        verifier.verify(&message, &keys[0].1, &keys[0].0).unwrap();
+        verifier.verify(&message, &keys[1].1, &keys[1].0).unwrap();
+        // Touch the first key so the second becomes the least recently used.
+        verifier.verify(&message, &keys[0].1, &keys[0].0).unwrap();
+        verifier.verify(&message, &keys[2].1, &keys[2].0).unwrap();
+
+        assert!(verifier.is_cached(&keys[0].0));
+        assert!(!verifier.is_cached(&keys[1].0));
+        assert!(verifier.is_cached(&keys[2].0));
+        assert_eq!(verifier.keys.len(), 2);
+        assert_eq!(verifier.recency.len(), 2);
+    }
+
+    #[test]
+    fn test_table_multiplication_matches_double_and_add() {
+        let key = derive_public_key(b"secret").unwrap();
+        let table = precompute(&key);
+        let scalars = [
+            BigInt::from(0),
+            BigInt::from(1),
+            BigInt::from(15),
+            BigInt::from(16),
+            BigInt::from(0x1234_5678u64),
+            &*baby_jubjub::R - 1,
+            (BigInt::from(1) << 256u32) - 1,
+        ];
+        for scalar in scalars.iter() {
+            assert_eq!(mul_with_table(&table, scalar), mul_point_escalar(&key, scalar.clone()));
+        }
+    }
+
+    #[test]
+    fn test_verify_with_table_agrees_with_verify_signature() {
+        let message = [2u8; 32];
+        let private_keys: Vec<[u8; 32]> = (1..=3u8).map(|i| [i; 32]).collect();
+        let public_keys: Vec<Point> = private_keys.iter().map(|pk| derive_public_key(pk).unwrap()).collect();
+        let table = PublicKeyTable::new(&public_keys).unwrap();
+        assert_eq!(table.len(), 3);
+
+        for (index, private_key) in private_keys.iter().enumerate() {
+            let signature = sign_message(private_key, &message).unwrap();
+            for (key_index, public_key) in public_keys.iter().enumerate() {
+                for msg in [message, [3u8; 32]] {
+                    assert_eq!(
+                        table.verify_with_table(key_index, &msg, &signature),
+                        verify_signature(&msg, &signature, public_key).unwrap()
+                    );
+                }
+            }
+            assert!(table.verify_with_table(index, &message, &signature));
+        }
+
+        let signature = sign_message(&private_keys[0], &message).unwrap();
+        assert!(!table.verify_with_table(3, &message, &signature));
+        assert!(!table.verify_with_table(0, &[0xffu8; 32], &signature));
+    }
+
+    #[test]
+    fn test_verify_with_table_reduces_messages_of_r_or_more() {
+        let public_key = derive_public_key(b"secret").unwrap();
+        let table = PublicKeyTable::new(std::slice::from_ref(&public_key)).unwrap();
+        let small = le_bigint_to_bytes(&BigInt::from(2), Some(32)).unwrap();
+        let large = le_bigint_to_bytes(&(&*baby_jubjub::R + 2), Some(32)).unwrap();
+
+        let signature = sign_message(b"secret", &large).unwrap();
+        assert!(verify_signature(&large, &signature, &public_key).unwrap());
+        assert!(table.verify_with_table(0, &large, &signature));
+        // R + 2 and 2 give the same challenge.
+        assert!(table.verify_with_table(0, &small, &signature));
+        assert!(!table.verify_with_table(0, &[0xffu8; 33], &signature));
+    }
+
+    #[test]
+    fn test_table_rejects_keys_outside_subgroup() {
+        let mut off_curve = derive_public_key(b"secret").unwrap();
+        off_curve.1 += 1;
+        let low_order = (BigInt::from(0), &*baby_jubjub::R - 1);
+        for key in [off_curve, low_order] {
+            assert!(matches!(
+                PublicKeyTable::new(&[key]),
+                Err(SemaphoreError::InvalidPublicKey(_))
+            ));
+        }
+        assert!(PublicKeyTable::new(&[]).unwrap().is_empty());
+    }
+}
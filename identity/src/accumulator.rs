@@ -0,0 +1,101 @@
+use baby_jubjub::{Fr, Point};
+use eddsa_poseidon::error::SemaphoreError;
+use eddsa_poseidon::hashing::PoseidonHasher2;
+use eddsa_poseidon::validate_public_key;
+use num_bigint::BigInt;
+
+/// Computes identity commitments one public key at a time, e.g. as registrations arrive,
+/// reusing a single Poseidon hasher.
+///
+/// Optionally keeps a running multiset digest: the sum modulo `R` of all commitments pushed
+/// so far. It does not depend on the order of the pushes, so two parties that received the
+/// same keys in any order can compare digests to detect a lost or extra registration. The sum
+/// is only an integrity check against accidents; someone choosing the keys can make two
+/// different sets collide.
+#[derive(Debug, Clone, Default)]
+pub struct CommitmentAccumulator {
+    hasher: PoseidonHasher2,
+    count: usize,
+    digest: Option<BigInt>,
+}
+
+impl CommitmentAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like `new`, also maintaining the multiset digest.
+    pub fn with_digest() -> Self {
+        Self {
+            digest: Some(BigInt::from(0)),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the commitment of `public_key` after checking it is a valid non-identity point,
+    /// and adds it to the count and digest. Rejected keys change nothing.
+    pub fn push(&mut self, public_key: &Point) -> Result<BigInt, SemaphoreError> {
+        validate_public_key(public_key)?;
+        let commitment = self.hasher.hash(&public_key.0, &public_key.1);
+        self.count += 1;
+        if let Some(digest) = self.digest.as_mut() {
+            *digest = Fr.add(digest, &commitment);
+        }
+        Ok(commitment)
+    }
+
+    /// Returns how many commitments have been computed.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the multiset digest, if the accumulator was built with `with_digest`.
+    pub fn digest(&self) -> Option<&BigInt> {
+        self.digest.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Identity;
+    use eddsa_poseidon::derive_public_key;
+
+    #[test]
+    fn test_push_matches_generate_commitment() {
+        let mut accumulator = CommitmentAccumulator::new();
+        assert_eq!(accumulator.count(), 0);
+        for i in 1..=4u8 {
+            let public_key = derive_public_key(&[i; 32]).unwrap();
+            let commitment = accumulator.push(&public_key).unwrap();
+            assert_eq!(commitment, Identity::generate_commitment(&public_key).unwrap());
+            assert_eq!(accumulator.count(), i as usize);
+        }
+        assert!(accumulator.digest().is_none());
+
+        let identity = (BigInt::from(0), BigInt::from(1));
+        assert!(accumulator.push(&identity).is_err());
+        assert_eq!(accumulator.count(), 4);
+    }
+
+    #[test]
+    fn test_digest_is_order_independent() {
+        let keys: Vec<Point> = (1..=4u8).map(|i| derive_public_key(&[i; 32]).unwrap()).collect();
+        let mut forward = CommitmentAccumulator::with_digest();
+        let mut backward = CommitmentAccumulator::with_digest();
+        for key in &keys {
+            forward.push(key).unwrap();
+        }
+        for key in keys.iter().rev() {
+            backward.push(key).unwrap();
+        }
+        assert_eq!(forward.digest(), backward.digest());
+
+        let mut partial = CommitmentAccumulator::with_digest();
+        for key in &keys[..3] {
+            partial.push(key).unwrap();
+        }
+        assert_eq!(partial.count(), 3);
+        assert_ne!(partial.digest(), forward.digest());
+    }
+}
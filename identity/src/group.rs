@@ -0,0 +1,324 @@
+use eddsa_poseidon::error::SemaphoreError;
+use eddsa_poseidon::hashing::{PoseidonHasher2, poseidon};
+use num_bigint::BigInt;
+use std::collections::HashMap;
+
+/// Largest supported tree depth, as in the Semaphore circuits.
+pub const MAX_DEPTH: usize = 32;
+
+/// A Semaphore group: a fixed-depth Poseidon Merkle tree of identity commitments, padded with
+/// zero leaves. Removed members leave a zero leaf behind, so the indices of the others never
+/// change. The root depends on the insertion order; when building a group from an unordered
+/// set of commitments, sort them with `sort_commitments` first so every party gets the same
+/// root.
+#[derive(Debug, Clone)]
+pub struct Group {
+    depth: usize,
+    zeros: Vec<BigInt>,
+    members: Vec<BigInt>,
+    indices: HashMap<BigInt, usize>,
+}
+
+impl Group {
+    /// Creates an empty group of the given depth, holding up to `2^depth` members.
+    /// `zeros[i]` is the root of an empty subtree of height `i`: `zeros[0] = 0` and
+    /// `zeros[i + 1] = poseidon2(zeros[i], zeros[i])`.
+    pub fn new(depth: usize) -> Result<Self, SemaphoreError> {
+        if !(1..=MAX_DEPTH).contains(&depth) {
+            return Err(SemaphoreError::InvalidParameter(format!(
+                "Depth must be between 1 and {}, got {}",
+                MAX_DEPTH, depth
+            )));
+        }
+
+        let mut hasher = PoseidonHasher2::new();
+        let mut zeros = Vec::with_capacity(depth + 1);
+        zeros.push(BigInt::from(0));
+        for i in 0..depth {
+            zeros.push(hasher.hash(&zeros[i], &zeros[i]));
+        }
+
+        Ok(Self {
+            depth,
+            zeros,
+            members: Vec::new(),
+            indices: HashMap::new(),
+        })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the root of the tree. For an empty group this is `zeros[depth]`.
+    pub fn root(&self) -> BigInt {
+        let mut hasher = PoseidonHasher2::new();
+        let mut level = self.members.clone();
+        for zero in &self.zeros[..self.depth] {
+            if level.is_empty() {
+                break;
+            }
+            if level.len() % 2 == 1 {
+                level.push(zero.clone());
+            }
+            level = level.chunks(2).map(|pair| hasher.hash(&pair[0], &pair[1])).collect();
+        }
+        level.pop().unwrap_or_else(|| self.zeros[self.depth].clone())
+    }
+
+    /// Builds the membership proof of the member at `index`. The proof has one sibling per
+    /// level, `depth` in all, zero subtrees included, and its `index` is the leaf index.
+    pub fn generate_proof(&self, index: usize) -> Result<MerkleProof, SemaphoreError> {
+        let leaf = self
+            .members
+            .get(index)
+            .filter(|leaf| **leaf != BigInt::from(0))
+            .ok_or_else(|| SemaphoreError::InvalidParameter(format!("No member at index {}", index)))?
+            .clone();
+
+        let mut hasher = PoseidonHasher2::new();
+        let mut level = self.members.clone();
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut position = index;
+        for zero in &self.zeros[..self.depth] {
+            if level.len() % 2 == 1 {
+                level.push(zero.clone());
+            }
+            siblings.push(level[position ^ 1].clone());
+            level = level.chunks(2).map(|pair| hasher.hash(&pair[0], &pair[1])).collect();
+            position >>= 1;
+        }
+
+        Ok(MerkleProof {
+            root: level.pop().expect("A group with a member has a root"),
+            leaf,
+            index: index as u64,
+            siblings,
+        })
+    }
+
+    /// Returns the leaves, including the zeros left by removed members.
+    pub fn members(&self) -> &[BigInt] {
+        &self.members
+    }
+
+    /// Returns the number of leaves, including removed members.
+    pub fn size(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Appends a commitment and returns its index. Zero, commitments already in the group and
+    /// additions to a full group are rejected.
+    pub fn add_member(&mut self, commitment: BigInt) -> Result<usize, SemaphoreError> {
+        if self.members.len() >= 1usize << self.depth {
+            return Err(SemaphoreError::InvalidParameter(format!("Group of depth {} is full", self.depth)));
+        }
+        if commitment == BigInt::from(0) {
+            return Err(SemaphoreError::InvalidParameter("Commitment cannot be 0".to_string()));
+        }
+        if self.contains(&commitment) {
+            return Err(SemaphoreError::InvalidParameter(format!("Commitment {} is already a member", commitment)));
+        }
+        let index = self.members.len();
+        self.indices.insert(commitment.clone(), index);
+        self.members.push(commitment);
+        Ok(index)
+    }
+
+    /// Removes the member at `index` by replacing its leaf with zero.
+    pub fn remove_member(&mut self, index: usize) -> Result<(), SemaphoreError> {
+        let leaf = self
+            .members
+            .get_mut(index)
+            .filter(|leaf| **leaf != BigInt::from(0))
+            .ok_or_else(|| SemaphoreError::InvalidParameter(format!("No member at index {}", index)))?;
+        self.indices.remove(leaf);
+        *leaf = BigInt::from(0);
+        Ok(())
+    }
+
+    /// Returns the index of `commitment`, in constant time on average.
+    pub fn index_of(&self, commitment: &BigInt) -> Option<usize> {
+        self.indices.get(commitment).copied()
+    }
+
+    pub fn contains(&self, commitment: &BigInt) -> bool {
+        self.indices.contains_key(commitment)
+    }
+}
+
+/// Membership proof for a leaf of a `Group`, the fixed-depth tree padded with zero leaves, as
+/// built by `Group::generate_proof`. There is one sibling per level, zero subtrees included,
+/// and `index` is the leaf index: bit `i` is set when the node at level `i` is a right child.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub root: BigInt,
+    pub leaf: BigInt,
+    pub index: u64,
+    pub siblings: Vec<BigInt>,
+}
+
+impl MerkleProof {
+    /// Recomputes the root from the leaf and siblings and compares it with `root`.
+    pub fn verify(&self) -> bool {
+        let mut node = self.leaf.clone();
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            let is_right = level < 64 && (self.index >> level) & 1 == 1;
+            let children = if is_right {
+                [sibling.clone(), node]
+            } else {
+                [node, sibling.clone()]
+            };
+            node = match poseidon(&children) {
+                Ok(hash) => hash,
+                Err(_) => return false,
+            };
+        }
+        node == self.root
+    }
+}
+
+/// Verifies that every proof has the given root, proves the matching leaf and is valid.
+/// Stops at the first failing proof. The slices must have the same length.
+pub fn verify_merkle_proofs(leaves: &[BigInt], proofs: &[MerkleProof], root: &BigInt) -> Result<bool, SemaphoreError> {
+    if leaves.len() != proofs.len() {
+        return Err(SemaphoreError::InvalidParameter(format!(
+            "Got {} leaves but {} proofs",
+            leaves.len(),
+            proofs.len()
+        )));
+    }
+
+    Ok(leaves
+        .iter()
+        .zip(proofs)
+        .all(|(leaf, proof)| &proof.root == root && &proof.leaf == leaf && proof.verify()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(a: &BigInt, b: &BigInt) -> BigInt {
+        poseidon(&[a.clone(), b.clone()]).unwrap()
+    }
+
+    /// Builds the four proofs of a depth-2 tree with leaves `1..=4`.
+    fn four_leaf_proofs() -> (Vec<BigInt>, Vec<MerkleProof>, BigInt) {
+        let leaves: Vec<BigInt> = (1..=4).map(BigInt::from).collect();
+        let left = hash(&leaves[0], &leaves[1]);
+        let right = hash(&leaves[2], &leaves[3]);
+        let root = hash(&left, &right);
+
+        let proofs = (0..4)
+            .map(|i| MerkleProof {
+                root: root.clone(),
+                leaf: leaves[i].clone(),
+                index: i as u64,
+                siblings: vec![
+                    leaves[i ^ 1].clone(),
+                    if i < 2 { right.clone() } else { left.clone() },
+                ],
+            })
+            .collect();
+        (leaves, proofs, root)
+    }
+
+    #[test]
+    fn test_group_membership_index() {
+        let mut group = Group::new(16).unwrap();
+        let commitments: Vec<BigInt> = (1..=3).map(BigInt::from).collect();
+        for (i, commitment) in commitments.iter().enumerate() {
+            assert_eq!(group.add_member(commitment.clone()).unwrap(), i);
+        }
+        assert!(group.add_member(commitments[1].clone()).is_err());
+        assert!(group.add_member(BigInt::from(0)).is_err());
+
+        assert!(group.contains(&commitments[1]));
+        assert_eq!(group.index_of(&commitments[1]), Some(1));
+        assert_eq!(group.index_of(&BigInt::from(4)), None);
+
+        group.remove_member(1).unwrap();
+        assert!(!group.contains(&commitments[1]));
+        assert_eq!(group.index_of(&commitments[1]), None);
+        assert_eq!(group.members()[1], BigInt::from(0));
+        assert_eq!(group.index_of(&commitments[2]), Some(2));
+        assert!(group.remove_member(1).is_err());
+        assert!(group.remove_member(5).is_err());
+
+        // A removed commitment can join again, at a new index.
+        assert_eq!(group.add_member(commitments[1].clone()).unwrap(), 3);
+        assert_eq!(group.index_of(&commitments[1]), Some(3));
+        assert_eq!(group.size(), 4);
+    }
+
+    #[test]
+    fn test_empty_group_roots() {
+        // The Poseidon zero-subtree hashes over a zero leaf, as published in `ZERO_BYTES` of
+        // light-hasher 1.1.0 (src/zero_bytes/poseidon.rs), entries 1 and 16 read big-endian.
+        assert_eq!(
+            Group::new(1).unwrap().root().to_string(),
+            "14744269619966411208579211824598458697587494354926760081771325075741142829156"
+        );
+        assert_eq!(
+            Group::new(16).unwrap().root().to_string(),
+            "19217088683336594659449020493828377907203207941212636669271704950158751593251"
+        );
+        assert!(Group::new(0).is_err());
+        assert!(Group::new(MAX_DEPTH + 1).is_err());
+    }
+
+    #[test]
+    fn test_group_root_matches_manual_tree() {
+        let (leaves, _, root) = four_leaf_proofs();
+        let mut group = Group::new(2).unwrap();
+        for leaf in &leaves {
+            group.add_member(leaf.clone()).unwrap();
+        }
+        assert_eq!(group.root(), root);
+        assert!(group.add_member(BigInt::from(5)).is_err());
+
+        let mut partial = Group::new(2).unwrap();
+        partial.add_member(leaves[0].clone()).unwrap();
+        let expected = hash(&hash(&leaves[0], &BigInt::from(0)), &hash(&BigInt::from(0), &BigInt::from(0)));
+        assert_eq!(partial.root(), expected);
+    }
+
+    #[test]
+    fn test_group_generate_proof() {
+        let mut group = Group::new(4).unwrap();
+        for member in 1..=3 {
+            group.add_member(BigInt::from(member)).unwrap();
+        }
+        for index in 0..3 {
+            let proof = group.generate_proof(index).unwrap();
+            assert_eq!(proof.siblings.len(), 4);
+            assert_eq!(proof.root, group.root());
+            assert_eq!(proof.leaf, BigInt::from(index + 1));
+            assert!(proof.verify());
+        }
+        // The last member's sibling is a zero leaf, and above it a zero subtree.
+        let proof = group.generate_proof(2).unwrap();
+        assert_eq!(proof.siblings[0], BigInt::from(0));
+        assert_eq!(proof.siblings[2], group.zeros[2]);
+
+        group.remove_member(1).unwrap();
+        assert!(group.generate_proof(1).is_err());
+        assert!(group.generate_proof(3).is_err());
+        assert!(group.generate_proof(0).unwrap().verify());
+    }
+
+    #[test]
+    fn test_verify_merkle_proofs() {
+        let (leaves, proofs, root) = four_leaf_proofs();
+        assert!(verify_merkle_proofs(&leaves[..3], &proofs[..3], &root).unwrap());
+        assert!(verify_merkle_proofs(&leaves, &proofs, &root).unwrap());
+
+        let mut tampered = proofs.clone();
+        tampered[3].siblings[0] += 1;
+        assert!(!verify_merkle_proofs(&leaves, &tampered, &root).unwrap());
+
+        assert!(!verify_merkle_proofs(&leaves, &proofs, &(&root + 1)).unwrap());
+        assert!(verify_merkle_proofs(&leaves, &proofs[..3], &root).is_err());
+    }
+}
@@ -1,72 +1,277 @@
-use ark_bn254::Fr as Fra;
-use baby_jubjub::Point;
-use eddsa_poseidon::util_functions::Signature;
+pub mod accumulator;
+pub mod group;
+#[cfg(any(test, feature = "test-util"))]
+pub mod vectors;
+
+use baby_jubjub::{BASE8, Point, SUBORDER, mul_point_escalar};
+use eddsa_poseidon::error::SemaphoreError;
+use group::MerkleProof;
+use eddsa_poseidon::hashing::{PoseidonHasher2, poseidon, poseidon_with_arity};
+use eddsa_poseidon::util_functions::{Signature, hash_input};
 use eddsa_poseidon::{
-    derive_public_key, derive_secret_scalar, sign_message as eddsa_sign_message,
+    KeyStrength, SupportedHashingAlgorithms, check_weak_key, derive_public_key, derive_secret_scalar, derive_secret_scalar_legacy, sign_message as eddsa_sign_message, sign_message_legacy, validate_public_key,
     verify_signature as eddsa_verify_signature,
 };
-use light_poseidon::{Poseidon, PoseidonHasher};
-use num_bigint::{BigInt, BigUint};
+use num_bigint::BigInt;
 use rand::Rng;
 use std::error::Error;
-use utils::conversions::{base64_to_buffer, buffer_to_base64, text_to_base64};
+use std::fmt;
+use serde_json::{json, Value};
+use tiny_keccak::{Hasher, Keccak};
+use utils::conversions::{
+    base64_to_buffer, be_bigint_to_bytes, buffer_to_base32, buffer_to_base64, decode_key_bytes, encode_key_bytes, field_to_hex32,
+    le_bigint_to_bytes, le_bytes_to_bigint,
+};
+use utils::type_checks::{is_object, is_string};
+
+pub fn poseidon2(nodes: Vec<String>) -> Result<BigInt, SemaphoreError> {
+    let inputs = nodes
+        .iter()
+        .map(|s| string_to_bigint(s))
+        .collect::<Result<Vec<BigInt>, SemaphoreError>>()?;
+
+    poseidon_with_arity(2, &inputs)
+}
+
+/// Hashes independent pairs with two-input Poseidon, in order, with the same results as
+/// `poseidon2` on each pair. The circom parameters are built once, or once per worker thread
+/// with the `parallel` feature, instead of once per pair. Inputs are reduced modulo `R`.
+pub fn poseidon2_batch(pairs: &[(BigInt, BigInt)]) -> Vec<BigInt> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        pairs
+            .par_iter()
+            .map_init(PoseidonHasher2::new, |hasher, (a, b)| hasher.hash(a, b))
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        let mut hasher = PoseidonHasher2::new();
+        pairs.iter().map(|(a, b)| hasher.hash(a, b)).collect()
+    }
+}
 
-fn string_to_biguint(num_str: &str) -> BigUint {
+fn string_to_bigint(num_str: &str) -> Result<BigInt, SemaphoreError> {
     num_str
         .parse()
-        .expect("Failed to parse the string into BigUint")
+        .map_err(|_| SemaphoreError::InvalidParameter(format!("'{}' is not a decimal integer", num_str)))
 }
 
-pub fn poseidon2(nodes: Vec<String>) -> String {
-    let mut poseidon = Poseidon::<Fra>::new_circom(2).unwrap();
+/// Hashes a Semaphore message or scope the way the v4 circuits and contracts expect:
+/// `keccak256(signal as 32-byte big-endian) >> 8`, so the result always fits in the field.
+/// Signals outside `[0, 2^256)` are reduced modulo `2^256` first.
+pub fn hash_signal(signal: &BigInt) -> BigInt {
+    let signal = utils::scalar::reduce(signal, &(BigInt::from(1) << 256u32));
+    let bytes = be_bigint_to_bytes(&signal, Some(32)).expect("signal fits in 32 bytes");
+    let mut hasher = Keccak::v256();
+    let mut digest = [0u8; 32];
+    hasher.update(&bytes);
+    hasher.finalize(&mut digest);
+    BigInt::from_bytes_be(num_bigint::Sign::Plus, &digest) >> 8u32
+}
 
-    let input1 = ark_bn254::Fr::from(string_to_biguint(&nodes[0]));
-    let input2 = ark_bn254::Fr::from(string_to_biguint(&nodes[1]));
+/// Returns the index of the first public key whose commitment equals `commitment`.
+pub fn find_commitment(commitment: &BigInt, candidates: &[Point]) -> Option<usize> {
+    candidates
+        .iter()
+        .position(|public_key| Identity::generate_commitment(public_key).as_ref() == Ok(commitment))
+}
 
-    let hash = poseidon.hash(&[input1, input2]).unwrap();
+/// Computes the commitment of each public key, in order, after checking it is a valid
+/// non-identity point. Runs in parallel with the `parallel` feature.
+pub fn commitments_from_public_keys(keys: &[Point]) -> Result<Vec<BigInt>, SemaphoreError> {
+    let commitment = |public_key: &Point| {
+        validate_public_key(public_key)?;
+        Identity::generate_commitment(public_key)
+    };
 
-    hash.to_string()
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        keys.par_iter().map(commitment).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        keys.iter().map(commitment).collect()
+    }
 }
 
-fn string_to_bigint(num_str: &str) -> BigInt {
-    num_str
-        .parse()
-        .expect("Failed to parse the string into BigUint")
+/// Maps a commitment to a leaf index of a tree of depth `tree_depth` as
+/// `commitment mod 2^tree_depth`, for sharding or slot-assignment schemes.
+///
+/// Distinct commitments collide whenever their low `tree_depth` bits agree, which becomes
+/// likely once a tree holds about `2^(tree_depth / 2)` members, so callers must handle
+/// occupied slots.
+///
+/// # Panics
+/// Panics if `tree_depth` is not lower than the number of bits of `usize`.
+pub fn commitment_to_slot(commitment: &BigInt, tree_depth: usize) -> usize {
+    assert!(tree_depth < usize::BITS as usize, "tree depth {} is too large", tree_depth);
+    let slot = utils::scalar::reduce(commitment, &(BigInt::from(1) << tree_depth));
+    usize::try_from(&slot).expect("slot fits in usize")
+}
+
+/// Returns the Merkle leaf of an identity commitment. Semaphore v4 inserts commitments into
+/// its tree as they are, without hashing them again, so this is the identity function; it is
+/// exposed so that callers building trees do not have to know that.
+pub fn leaf_hash(commitment: &BigInt) -> BigInt {
+    commitment.clone()
+}
+
+/// Sorts commitments into the canonical order used to build a `Group` from an unordered set:
+/// ascending by their 32-byte big-endian encoding, which for field elements is ascending
+/// numeric order. Sorting first makes the group root independent of the order in which the
+/// commitments were collected.
+pub fn sort_commitments(commitments: &mut [BigInt]) {
+    commitments.sort_unstable();
+}
+
+/// Reverses `Identity::commitment_bytes`, reading the key as a big-endian integer. The range
+/// is not checked: keys of `R` or more decode to values that are not commitments.
+pub fn commitment_from_bytes(bytes: &[u8; 32]) -> BigInt {
+    BigInt::from_bytes_be(num_bigint::Sign::Plus, bytes)
 }
 
-#[derive(Debug, Clone)]
+/// Version byte prepended to exports, followed by the key as encoded by `encode_key_bytes`.
+/// Version bytes count down from `0xff`.
+pub const EXPORT_VERSION: u8 = 0xfe;
+
+/// Version byte of the first versioned exports, followed by the bare private key. Still
+/// imported, no longer produced.
+pub const EXPORT_VERSION_RAW: u8 = 0xff;
+
+/// Lowest byte reserved for export versions. Legacy exports of binary keys starting with a
+/// byte in this range are ambiguous and must be re-exported.
+const RESERVED_VERSION_START: u8 = 0xf8;
+
+/// Domain tag appended to the private key to derive `Identity::viewing_key`.
+pub const VIEWING_KEY_TAG: &[u8] = b"semaphore-identity-view";
+
+/// How the secret scalar is derived from the private key.
+///
+/// `Fixed` clamps the hashed key like zk-kit and is the default. `Legacy` reproduces earlier
+/// releases of this crate, which skipped the clamping: for most keys it yields a different
+/// secret scalar, public key and commitment, incompatible with Semaphore. It exists only to
+/// recover identities whose commitments were stored by those releases; such identities sign
+/// with `eddsa_poseidon::sign_message_legacy`, so their signatures verify under their own
+/// public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DerivationMode {
+    Legacy,
+    #[default]
+    Fixed,
+}
+
+#[derive(Clone)]
 pub struct Identity {
     private_key: Vec<u8>,
     secret_scalar: num_bigint::BigInt,
     public_key: Point,
     commitment: num_bigint::BigInt,
+    derivation_mode: DerivationMode,
+}
+
+impl fmt::Debug for Identity {
+    /// Redacts the private key and secret scalar so identities can be logged safely.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Identity")
+            .field("private_key", &format_args!("<redacted {} bytes>", self.private_key.len()))
+            .field("secret_scalar", &format_args!("<redacted>"))
+            .field("public_key", &self.public_key)
+            .field("commitment", &self.commitment)
+            .field("derivation_mode", &self.derivation_mode)
+            .finish()
+    }
 }
 
 impl Identity {
     /// Creates a new Semaphore identity instance from a private key (optional).
+    /// Empty or all-zero keys (`SemaphoreError::WeakKey`) and keys deriving the identity point
+    /// as public key are rejected. Other guessable keys are accepted; check `key_strength` to
+    /// warn about them.
     pub fn new(private_key: Option<Vec<u8>>) -> Result<Self, Box<dyn Error>> {
+        Identity::with_derivation_mode(private_key, DerivationMode::Fixed)
+    }
+
+    /// Like `new`, with an explicit `DerivationMode`. See its documentation before using
+    /// `DerivationMode::Legacy`.
+    pub fn with_derivation_mode(private_key: Option<Vec<u8>>, mode: DerivationMode) -> Result<Self, Box<dyn Error>> {
         let mut rng = rand::rng();
         let mut key = [0u8; 32];
         rng.fill(&mut key);
-        let private_key = private_key.unwrap_or_else(|| (&key).to_vec());
-        let secret_scalar = derive_secret_scalar(&private_key)?;
-        let public_key = derive_public_key(&private_key)?;
-        let public_key_strings = vec![public_key.0.to_string(), public_key.1.to_string()];
-        let commitment = string_to_bigint(&poseidon2(public_key_strings));
+        let private_key = private_key.unwrap_or_else(|| key.to_vec());
+        let (secret_scalar, public_key) = match mode {
+            DerivationMode::Fixed => (derive_secret_scalar(&private_key)?, derive_public_key(&private_key)?),
+            DerivationMode::Legacy => {
+                check_weak_key(&private_key)?;
+                let secret_scalar = derive_secret_scalar_legacy(&private_key);
+                let public_key = mul_point_escalar(&BASE8, secret_scalar.clone());
+                validate_public_key(&public_key)?;
+                (secret_scalar, public_key)
+            }
+        };
+        let commitment = Identity::generate_commitment(&public_key)?;
+        #[cfg(feature = "trace")]
+        log::debug!("public key: ({}, {}), commitment: {}", public_key.0, public_key.1, commitment);
 
         Ok(Self {
             private_key,
             secret_scalar,
             public_key,
             commitment,
+            derivation_mode: mode,
+        })
+    }
+
+    /// Creates an identity from a BIP-39 English mnemonic: the 64-byte seed derived from the
+    /// phrase and `passphrase` is used as the private key. The word list and checksum are
+    /// validated.
+    #[cfg(feature = "bip39")]
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, SemaphoreError> {
+        let mnemonic = bip39::Mnemonic::parse_normalized(phrase).map_err(|e| SemaphoreError::InvalidMnemonic(e.to_string()))?;
+        Identity::new(Some(mnemonic.to_seed(passphrase).to_vec()))
+            .map_err(|e| SemaphoreError::InvalidPrivateKey(e.to_string()))
+    }
+
+    /// Builds an identity from a secret scalar alone, e.g. when the private key was discarded.
+    /// The resulting identity has an empty private key: it can compute its public key and
+    /// commitment, but `sign_message` fails and `export` does not round-trip.
+    pub fn from_secret_scalar(scalar: &BigInt) -> Result<Self, SemaphoreError> {
+        if scalar <= &BigInt::from(0) || scalar >= &*SUBORDER {
+            return Err(SemaphoreError::InvalidParameter(
+                "Secret scalar must be in the range (0, SUBORDER)".to_string(),
+            ));
+        }
+        let public_key = mul_point_escalar(&BASE8, scalar.clone());
+        validate_public_key(&public_key)?;
+        let commitment = Identity::generate_commitment(&public_key)?;
+
+        Ok(Self {
+            private_key: Vec::new(),
+            secret_scalar: scalar.clone(),
+            public_key,
+            commitment,
+            derivation_mode: DerivationMode::Fixed,
         })
     }
 
+    /// Exports the secret scalar as a zero-padded 64-character hexadecimal string.
+    pub fn secret_scalar_hex(&self) -> String {
+        field_to_hex32(&self.secret_scalar).expect("Secret scalar is a field element")
+    }
+
     /// Returns the private key.
     pub fn private_key(&self) -> &Vec<u8> {
         &self.private_key
     }
 
+    /// Returns the heuristic strength estimate of the private key, see
+    /// `eddsa_poseidon::estimate_key_strength`.
+    pub fn key_strength(&self) -> KeyStrength {
+        eddsa_poseidon::estimate_key_strength(&self.private_key)
+    }
+
     /// Returns the secret scalar.
     pub fn secret_scalar(&self) -> &num_bigint::BigInt {
         &self.secret_scalar
@@ -82,27 +287,182 @@ impl Identity {
         &self.commitment
     }
 
-    /// Exports the private key as base64.
-    pub fn export(&self) -> String {
-        if let Ok(utf8_str) = std::str::from_utf8(&self.private_key) {
-            text_to_base64(utf8_str)
+    /// Returns the commitment as a fixed-width storage key: its 32-byte big-endian encoding,
+    /// zero-padded. Equal commitments always give equal keys; `commitment_from_bytes` reverses it.
+    pub fn commitment_bytes(&self) -> [u8; 32] {
+        let bytes = be_bigint_to_bytes(&self.commitment, Some(32)).expect("Commitment is a field element");
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&bytes);
+        key
+    }
+
+    /// Returns a short display fingerprint of the commitment: the first 8 bytes of the
+    /// BLAKE-512 hash of its 32-byte big-endian encoding, base32-encoded.
+    /// Meant for UIs and logs only; it is not cryptographically binding by itself.
+    pub fn fingerprint(&self) -> String {
+        let bytes = be_bigint_to_bytes(&self.commitment, Some(32)).expect("Commitment is a field element");
+        buffer_to_base32(&hash_input(&bytes)[..8])
+    }
+
+    /// Deterministically derives a child identity whose private key is the first 32 bytes of
+    /// `hash(master_private_key || index)`, with `index` encoded as 4 big-endian bytes.
+    /// Children cannot be linked to each other or to the master without the master private key.
+    pub fn derive_child(&self, index: u32) -> Result<Identity, SemaphoreError> {
+        let input = [self.private_key.as_slice(), &index.to_be_bytes()].concat();
+        let child_key = hash_input(&input)[..32].to_vec();
+        Identity::new(Some(child_key)).map_err(|e| SemaphoreError::InvalidPrivateKey(e.to_string()))
+    }
+
+    /// Derives a viewing key: `hash(private_key || VIEWING_KEY_TAG)`, truncated to 32 bytes,
+    /// read little-endian and reduced modulo the subgroup order.
+    ///
+    /// The hash is one-way and domain separated from every other use of the private key, so
+    /// the viewing key can be shared without revealing the private key, the secret scalar or
+    /// any signing ability. Identities built with `Identity::from_secret_scalar` have no
+    /// private key and hash the 32-byte little-endian secret scalar in its place, so their
+    /// viewing key differs from that of the identity they were built from.
+    pub fn viewing_key(&self) -> BigInt {
+        let key = if self.private_key.is_empty() {
+            le_bigint_to_bytes(&self.secret_scalar, Some(32)).expect("Secret scalar is a field element")
         } else {
-            buffer_to_base64(&self.private_key)
+            self.private_key.clone()
+        };
+        let input = [key.as_slice(), VIEWING_KEY_TAG].concat();
+        let hash = hash_input(&input);
+        le_bytes_to_bigint(&hash[..32]) % &*SUBORDER
+    }
+
+    /// Returns true if `private_key` derives `commitment` when hashed with `algorithm`, to
+    /// diagnose which algorithm produced a commitment. Keys that cannot derive an identity
+    /// never match.
+    pub fn matches_with_algorithm(private_key: &[u8], commitment: &BigInt, algorithm: SupportedHashingAlgorithms) -> bool {
+        match algorithm {
+            SupportedHashingAlgorithms::Blake1 => Identity::new(Some(private_key.to_vec()))
+                .is_ok_and(|identity| &identity.commitment == commitment),
         }
     }
 
-    /// Imports an identity from a base64-encoded private key.
+    /// Returns true if both identities share the same commitment.
+    pub fn commitment_collides_with(&self, other: &Identity) -> bool {
+        self.commitment == other.commitment
+    }
+
+    /// Exports the private key as base64 of the `EXPORT_VERSION` byte followed by the
+    /// `encode_key_bytes` encoding of the key, whose length prefix catches truncated exports.
+    pub fn export(&self) -> String {
+        let encoded = encode_key_bytes(&self.private_key);
+        let mut payload = Vec::with_capacity(encoded.len() + 1);
+        payload.push(EXPORT_VERSION);
+        payload.extend_from_slice(&encoded);
+        buffer_to_base64(&payload)
+    }
+
+    /// Imports an identity exported by `export`. Versioned exports are recognised by a first
+    /// byte in `0xf8..=0xff`, which never starts valid UTF-8; anything else is read as a legacy
+    /// export, i.e. the bare base64 private key. Both `EXPORT_VERSION` and `EXPORT_VERSION_RAW`
+    /// exports are accepted; unknown versions fail with `SemaphoreError::UnsupportedExportVersion`.
     pub fn import(encoded: &str) -> Result<Self, Box<dyn Error>> {
-        let private_key = base64_to_buffer(encoded)?;
+        let payload = base64_to_buffer(encoded)?;
+        let private_key = match payload.first() {
+            Some(&EXPORT_VERSION) => decode_key_bytes(&payload[1..])?,
+            Some(&EXPORT_VERSION_RAW) => payload[1..].to_vec(),
+            Some(&version) if version >= RESERVED_VERSION_START => {
+                return Err(SemaphoreError::UnsupportedExportVersion(version).into());
+            }
+            _ => payload,
+        };
         Identity::new(Some(private_key))
     }
 
+    /// Imports a list of base64-encoded private keys, keeping one result per entry
+    /// instead of stopping at the first invalid one.
+    pub fn import_many(encoded: &[String]) -> Vec<Result<Self, SemaphoreError>> {
+        encoded
+            .iter()
+            .map(|e| Identity::import(e).map_err(|e| SemaphoreError::InvalidPrivateKey(e.to_string())))
+            .collect()
+    }
+
+    /// Imports an identity and checks that it derives to `expected_commitment`,
+    /// catching truncated or tampered exports that still decode as base64.
+    pub fn import_verified(encoded: &str, expected_commitment: &BigInt) -> Result<Self, SemaphoreError> {
+        let identity = Identity::import(encoded).map_err(|e| SemaphoreError::InvalidPrivateKey(e.to_string()))?;
+        if &identity.commitment != expected_commitment {
+            return Err(SemaphoreError::CommitmentMismatch(format!(
+                "expected {}, got {}",
+                expected_commitment, identity.commitment
+            )));
+        }
+        Ok(identity)
+    }
+
+    /// Imports an identity from a JSON object of the form
+    /// `{ "privateKey": "<base64>", "hashingAlgorithm": "blake1" }`.
+    /// The `hashingAlgorithm` field is optional.
+    pub fn from_json(json: &Value) -> Result<Self, SemaphoreError> {
+        if !is_object(json) {
+            return Err(SemaphoreError::InvalidParameter("Identity JSON must be an object".to_string()));
+        }
+
+        let private_key = json
+            .get("privateKey")
+            .ok_or_else(|| SemaphoreError::InvalidParameter("Missing 'privateKey'".to_string()))?;
+        if !is_string(private_key) {
+            return Err(SemaphoreError::InvalidParameter("'privateKey' must be a string".to_string()));
+        }
+
+        if let Some(algorithm) = json.get("hashingAlgorithm")
+            && (!is_string(algorithm) || algorithm.as_str() != Some("blake1"))
+        {
+            return Err(SemaphoreError::InvalidParameter(
+                "Unsupported 'hashingAlgorithm'".to_string(),
+            ));
+        }
+
+        Identity::import(private_key.as_str().unwrap_or_default())
+            .map_err(|e| SemaphoreError::InvalidPrivateKey(e.to_string()))
+    }
+
+    /// Exports the identity as a JSON object accepted by `Identity::from_json`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "privateKey": self.export(),
+            "hashingAlgorithm": "blake1",
+        })
+    }
+
+    /// Bundles the inputs of the Semaphore v4 circuit for this identity, in the circuit's
+    /// order: `secret`, `merkleProofLength`, `merkleProofIndex`, `merkleProofSiblings`, `scope`
+    /// and `message`. `scope` and `message` are hashed with `hash_signal`. `merkle_proof` is a
+    /// proof from `Group::generate_proof`, with one sibling per level, so the proof length is
+    /// the group depth and the proof index is the leaf index. All values are decimal strings.
+    pub fn proof_inputs(&self, merkle_proof: &MerkleProof, scope: &BigInt, message: &BigInt) -> Value {
+        let length = merkle_proof.siblings.len();
+        let siblings: Vec<String> = merkle_proof.siblings.iter().map(|s| s.to_string()).collect();
+
+        json!({
+            "secret": self.secret_scalar.to_string(),
+            "merkleProofLength": length.to_string(),
+            "merkleProofIndex": merkle_proof.index.to_string(),
+            "merkleProofSiblings": siblings,
+            "scope": hash_signal(scope).to_string(),
+            "message": hash_signal(message).to_string(),
+        })
+    }
+
     /// Signs a message with the private key.
+    /// Fails for identities built with `Identity::from_secret_scalar`.
     pub fn sign_message(
         &self,
         message: &[u8],
     ) -> Result<Signature, Box<dyn Error>> {
-        eddsa_sign_message(&self.private_key, &message)
+        if self.private_key.is_empty() {
+            return Err(SemaphoreError::InvalidPrivateKey("Identity has no private key".to_string()).into());
+        }
+        match self.derivation_mode {
+            DerivationMode::Fixed => eddsa_sign_message(&self.private_key, message),
+            DerivationMode::Legacy => sign_message_legacy(&self.private_key, message),
+        }
     }
 
     /// Verifies a signature with the given public key.
@@ -111,13 +471,20 @@ impl Identity {
         signature: &Signature,
         public_key: &Point,
     ) -> Result<bool, Box<dyn Error>> {
-        eddsa_verify_signature(&message, signature, public_key)
+        eddsa_verify_signature(message, signature, public_key)
+    }
+
+    /// Generates a commitment from public key coordinates given as decimal strings,
+    /// checking that they form a valid point on the curve.
+    pub fn commitment_from_coords(x: &str, y: &str) -> Result<BigInt, SemaphoreError> {
+        let public_key = (string_to_bigint(x)?, string_to_bigint(y)?);
+        validate_public_key(&public_key)?;
+        Identity::generate_commitment(&public_key)
     }
 
     /// Generates a commitment from a given public key.
-    pub fn generate_commitment(public_key: &Point) -> num_bigint::BigInt {
-        let public_key_strings = vec![public_key.0.to_string(), public_key.1.to_string()];
-        string_to_bigint(&poseidon2(public_key_strings))
+    pub fn generate_commitment(public_key: &Point) -> Result<num_bigint::BigInt, SemaphoreError> {
+        poseidon(&[public_key.0.clone(), public_key.1.clone()])
     }
 }
 
@@ -125,25 +492,242 @@ impl Identity {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_key_strength() {
+        assert_eq!(Identity::new(Some(b"secret".to_vec())).unwrap().key_strength(), KeyStrength::Weak);
+        assert_eq!(Identity::new(None).unwrap().key_strength(), KeyStrength::Strong);
+    }
+
     #[test]
     fn test_identity_random() {
         let identity = Identity::new(None).unwrap();
         let pk = identity.private_key();
         assert_eq!(pk.len(), 32);
-        assert_eq!(identity.secret_scalar(), &derive_secret_scalar(&pk).unwrap());
-        assert_eq!(identity.public_key(), &derive_public_key(&pk).unwrap());
+        assert_eq!(identity.secret_scalar(), &derive_secret_scalar(pk).unwrap());
+        assert_eq!(identity.public_key(), &derive_public_key(pk).unwrap());
         let public_key_strings = vec![
             identity.public_key().0.to_string(),
             identity.public_key().1.to_string(),
         ];
-        let computed_commitment = string_to_bigint(&poseidon2(public_key_strings));
+        let computed_commitment = poseidon2(public_key_strings).unwrap();
         assert_eq!(identity.commitment(), &computed_commitment);
     }
 
+    #[test]
+    fn test_hash_signal() {
+        // keccak256 of 32 zero bytes is 0x290decd9...e563, shifted right by one byte.
+        assert_eq!(
+            hash_signal(&BigInt::from(0)),
+            BigInt::parse_bytes(b"290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5", 16).unwrap()
+        );
+        // keccak256(uint256(1)) is 0xb10e2d52...fa0cf6.
+        assert_eq!(
+            hash_signal(&BigInt::from(1)).to_string(),
+            "312829776796408387545637016147278514583116203736587368460269838669765409292"
+        );
+        let huge = (BigInt::from(1) << 256u32) - 1;
+        assert!(hash_signal(&huge) < (BigInt::from(1) << 248u32));
+        assert_eq!(hash_signal(&(BigInt::from(1) << 256u32)), hash_signal(&BigInt::from(0)));
+    }
+
+    #[test]
+    fn test_proof_inputs_shape() {
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let mut group = group::Group::new(2).unwrap();
+        group.add_member(BigInt::from(3)).unwrap();
+        group.add_member(BigInt::from(4)).unwrap();
+        group.add_member(identity.commitment().clone()).unwrap();
+        let proof = group.generate_proof(2).unwrap();
+        let inputs = identity.proof_inputs(&proof, &BigInt::from(1), &BigInt::from(0));
+        let keys: Vec<&str> = inputs.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["secret", "merkleProofLength", "merkleProofIndex", "merkleProofSiblings", "scope", "message"]
+        );
+
+        assert_eq!(inputs["secret"], identity.secret_scalar().to_string());
+        assert_eq!(inputs["merkleProofLength"], "2");
+        assert_eq!(inputs["merkleProofIndex"], "2");
+        let siblings: Vec<String> = proof.siblings.iter().map(|s| s.to_string()).collect();
+        assert_eq!(inputs["merkleProofSiblings"], json!(siblings));
+        assert_eq!(proof.siblings[0], BigInt::from(0));
+        assert_eq!(inputs["scope"], hash_signal(&BigInt::from(1)).to_string());
+        assert_eq!(inputs["message"], hash_signal(&BigInt::from(0)).to_string());
+    }
+
+    #[test]
+    fn test_commitments_from_public_keys() {
+        let keys: Vec<Point> = (1..=4u8)
+            .map(|i| Identity::new(Some(vec![i; 32])).unwrap().public_key().clone())
+            .collect();
+        let expected: Vec<BigInt> = keys.iter().map(|k| Identity::generate_commitment(k).unwrap()).collect();
+        assert_eq!(commitments_from_public_keys(&keys).unwrap(), expected);
+        assert!(commitments_from_public_keys(&[]).unwrap().is_empty());
+
+        let mut invalid = keys.clone();
+        invalid[2].1 += 1;
+        assert!(matches!(
+            commitments_from_public_keys(&invalid),
+            Err(SemaphoreError::InvalidPublicKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_identity_rejects_all_zero_key() {
+        for mode in [DerivationMode::Fixed, DerivationMode::Legacy] {
+            let err = Identity::with_derivation_mode(Some(vec![0u8; 32]), mode).unwrap_err();
+            assert_eq!(err.downcast_ref::<SemaphoreError>(), Some(&SemaphoreError::WeakKey));
+        }
+        let mut key = vec![0u8; 32];
+        key[0] = 1;
+        assert!(Identity::new(Some(key)).is_ok());
+    }
+
+    #[test]
+    fn test_derivation_modes() {
+        let key = b"secret".to_vec();
+        let fixed = Identity::with_derivation_mode(Some(key.clone()), DerivationMode::Fixed).unwrap();
+        let legacy = Identity::with_derivation_mode(Some(key.clone()), DerivationMode::Legacy).unwrap();
+
+        assert_eq!(fixed.commitment(), Identity::new(Some(key)).unwrap().commitment());
+        assert_eq!(fixed.commitment().to_string(), "21756852044673293804725356853298692762259855200429755225624171532449447776732");
+        assert_eq!(legacy.commitment().to_string(), "19207788032997656884218644059550927145952524257682489683066744313126255948790");
+
+        let signature = legacy.sign_message(b"message").unwrap();
+        assert!(Identity::verify_signature(b"message", &signature, legacy.public_key()).unwrap());
+        assert!(!Identity::verify_signature(b"message", &signature, fixed.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_poseidon2_batch_matches_poseidon2() {
+        let pairs: Vec<(BigInt, BigInt)> = (0..20u64)
+            .map(|i| (BigInt::from(i * 7919), BigInt::from(i) << 200u32))
+            .chain([(BigInt::from(-1), &*baby_jubjub::R + 3)])
+            .collect();
+        let batch = poseidon2_batch(&pairs);
+        assert_eq!(batch.len(), pairs.len());
+        for ((a, b), hash) in pairs.iter().zip(&batch) {
+            let expected = poseidon(&[a.clone(), b.clone()]).unwrap();
+            assert_eq!(hash, &expected);
+            if a.sign() != num_bigint::Sign::Minus && b < &*baby_jubjub::R {
+                assert_eq!(hash, &poseidon2(vec![a.to_string(), b.to_string()]).unwrap());
+            }
+        }
+        assert!(poseidon2_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_poseidon_hasher2_matches_poseidon2() {
+        let mut hasher = eddsa_poseidon::hashing::PoseidonHasher2::new();
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let (x, y) = identity.public_key();
+        assert_eq!(hasher.hash(x, y), poseidon2(vec![x.to_string(), y.to_string()]).unwrap());
+        assert_eq!(&hasher.hash(x, y), identity.commitment());
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace_logs_derivation_steps() {
+        use std::sync::Mutex;
+
+        struct Capture(Mutex<Vec<String>>);
+
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+            fn log(&self, record: &log::Record) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+        log::set_logger(&CAPTURE).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let lines = CAPTURE.0.lock().unwrap();
+        for prefix in ["private key hash: ", "clamped bytes: ", "secret scalar: ", "public key: "] {
+            assert!(lines.iter().any(|line| line.starts_with(prefix)), "missing '{}'", prefix);
+        }
+        assert!(lines.iter().any(|line| line.ends_with(&identity.commitment().to_string())));
+    }
+
+    #[test]
+    fn test_commitment_to_slot() {
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        for depth in [0, 1, 8, 16, 32] {
+            let slot = commitment_to_slot(identity.commitment(), depth);
+            assert!(slot < 1 << depth);
+            assert_eq!(slot, commitment_to_slot(identity.commitment(), depth));
+        }
+        assert_eq!(commitment_to_slot(&BigInt::from(0x1234), 8), 0x34);
+        for i in 0..50u8 {
+            assert!(commitment_to_slot(&(BigInt::from(i) * 1_000_003), 4) < 16);
+        }
+    }
+
+    #[test]
+    fn test_leaf_hash_is_the_commitment() {
+        // Semaphore v4 groups use the commitment itself as the leaf.
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let leaf = leaf_hash(identity.commitment());
+        assert_eq!(leaf.to_string(), "21756852044673293804725356853298692762259855200429755225624171532449447776732");
+
+        let mut group = group::Group::new(1).unwrap();
+        group.add_member(identity.commitment().clone()).unwrap();
+        assert_eq!(group.members(), std::slice::from_ref(&leaf));
+        assert_eq!(group.root(), poseidon(&[leaf, BigInt::from(0)]).unwrap());
+    }
+
+    #[test]
+    fn test_sort_commitments_gives_order_independent_root() {
+        let commitments: Vec<BigInt> = (1..=5u8)
+            .map(|i| Identity::new(Some(vec![i; 32])).unwrap().commitment().clone())
+            .collect();
+        let mut forward = commitments.clone();
+        let mut backward: Vec<BigInt> = commitments.iter().rev().cloned().collect();
+        sort_commitments(&mut forward);
+        sort_commitments(&mut backward);
+        assert_eq!(forward, backward);
+        assert!(forward.windows(2).all(|pair| {
+            be_bigint_to_bytes(&pair[0], Some(32)).unwrap() < be_bigint_to_bytes(&pair[1], Some(32)).unwrap()
+        }));
+
+        let root = |members: &[BigInt]| {
+            let mut group = group::Group::new(4).unwrap();
+            for member in members {
+                group.add_member(member.clone()).unwrap();
+            }
+            group.root()
+        };
+        assert_eq!(root(&forward), root(&backward));
+        assert_ne!(root(&commitments), root(&commitments.iter().rev().cloned().collect::<Vec<_>>()));
+    }
+
+    #[cfg(feature = "bip39")]
+    #[test]
+    fn test_identity_from_mnemonic() {
+        // BIP-39 reference vector: this phrase with passphrase "TREZOR" gives seed c55257c3...3b04.
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let identity = Identity::from_mnemonic(phrase, "TREZOR").unwrap();
+        assert_eq!(
+            utils::conversions::buffer_to_hex(identity.private_key()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        assert_eq!(identity.commitment().to_string(), "4847287179407009695833799508448452454680051819174390373065569339011485674317");
+        assert_ne!(Identity::from_mnemonic(phrase, "").unwrap().commitment(), identity.commitment());
+
+        let bad_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(matches!(Identity::from_mnemonic(bad_checksum, ""), Err(SemaphoreError::InvalidMnemonic(_))));
+        assert!(matches!(Identity::from_mnemonic("not a mnemonic", ""), Err(SemaphoreError::InvalidMnemonic(_))));
+    }
+
     #[test]
     fn test_identity_from_string() {
         // let private_key = "secret".as_bytes().to_vec();
-        let private_key = Some(b"secret".to_vec()).unwrap();
+        let private_key = b"secret".to_vec();
         let identity = Identity::new(Some(private_key.clone())).unwrap();
         assert_eq!(identity.secret_scalar(), &derive_secret_scalar(&private_key).unwrap());
         assert_eq!(identity.public_key(), &derive_public_key(&private_key).unwrap());
@@ -151,7 +735,7 @@ mod tests {
             identity.public_key().0.to_string(),
             identity.public_key().1.to_string(),
         ];
-        let computed_commitment = string_to_bigint(&poseidon2(public_key_strings));
+        let computed_commitment = poseidon2(public_key_strings).unwrap();
         println!("PrivateKey: {:?}, SecretScalar: {:?}, Commitment: {:?}", String::from_utf8_lossy(identity.private_key()), identity.secret_scalar(), identity.commitment());
         assert_eq!(String::from_utf8_lossy(identity.private_key()), "secret");
         assert_eq!(identity.commitment(), &computed_commitment);
@@ -178,10 +762,254 @@ mod tests {
     //     assert!(verification_result.unwrap_or(false));
     // }
 
+    #[test]
+    fn test_export_round_trips_multibyte_utf8_keys() {
+        for key in ["café", "naïve 秘密 🔑"] {
+            let identity = Identity::new(Some(key.as_bytes().to_vec())).unwrap();
+            let imported = Identity::import(&identity.export()).unwrap();
+            assert_eq!(imported.private_key().as_slice(), key.as_bytes());
+            assert_eq!(String::from_utf8(imported.private_key().clone()).unwrap(), key);
+            assert_eq!(imported.commitment(), identity.commitment());
+
+            let from_json = Identity::from_json(&identity.to_json()).unwrap();
+            assert_eq!(from_json.private_key(), identity.private_key());
+        }
+    }
+
+    #[test]
+    fn test_import_legacy_and_versioned_exports() {
+        let identity = Identity::new(Some(b"some key".to_vec())).unwrap();
+
+        let legacy = buffer_to_base64(b"some key");
+        assert_eq!(legacy, "c29tZSBrZXk=");
+        assert_eq!(Identity::import(&legacy).unwrap().commitment(), identity.commitment());
+
+        let versioned = identity.export();
+        assert_eq!(base64_to_buffer(&versioned).unwrap()[0], EXPORT_VERSION);
+        assert_ne!(versioned, legacy);
+        assert_eq!(Identity::import(&versioned).unwrap().private_key(), identity.private_key());
+
+        let raw = buffer_to_base64(&[&[EXPORT_VERSION_RAW][..], b"some key"].concat());
+        assert_eq!(Identity::import(&raw).unwrap().commitment(), identity.commitment());
+
+        let future = buffer_to_base64(&[0xfd, 1, 2, 3]);
+        let err = Identity::import(&future).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SemaphoreError>(),
+            Some(&SemaphoreError::UnsupportedExportVersion(0xfd))
+        );
+    }
+
+    #[test]
+    fn test_export_uses_encode_key_bytes() {
+        let identity = Identity::new(Some(b"some key".to_vec())).unwrap();
+        let payload = base64_to_buffer(&identity.export()).unwrap();
+        assert_eq!(payload[0], EXPORT_VERSION);
+        assert_eq!(payload[1..], encode_key_bytes(b"some key"));
+
+        let binary = Identity::new(Some(vec![0xf0, 0x9f, 0x00, 0xc3])).unwrap();
+        assert_eq!(Identity::import(&binary.export()).unwrap().private_key(), binary.private_key());
+
+        // The length prefix rejects a truncated export instead of importing a shorter key.
+        let truncated = buffer_to_base64(&payload[..payload.len() - 1]);
+        assert!(Identity::import(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_identity_import_verified() {
+        let identity = Identity::new(Some(b"some key".to_vec())).unwrap();
+        let exported = identity.export();
+
+        let imported = Identity::import_verified(&exported, identity.commitment()).unwrap();
+        assert_eq!(imported.private_key(), identity.private_key());
+
+        let wrong = identity.commitment() + 1;
+        assert!(matches!(
+            Identity::import_verified(&exported, &wrong),
+            Err(SemaphoreError::CommitmentMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_identity_import_many() {
+        let first = Identity::new(Some(b"first".to_vec())).unwrap();
+        let second = Identity::new(Some(b"second".to_vec())).unwrap();
+        let encoded = vec![first.export(), "not base64!".to_string(), second.export()];
+
+        let results = Identity::import_many(&encoded);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().commitment(), first.commitment());
+        assert!(matches!(results[1], Err(SemaphoreError::InvalidPrivateKey(_))));
+        assert_eq!(results[2].as_ref().unwrap().commitment(), second.commitment());
+    }
+
+    #[test]
+    fn test_identity_json_roundtrip() {
+        let identity = Identity::new(Some(b"json key".to_vec())).unwrap();
+        let json = identity.to_json();
+        assert_eq!(json["hashingAlgorithm"], "blake1");
+        let imported = Identity::from_json(&json).unwrap();
+        assert_eq!(imported.private_key(), identity.private_key());
+        assert_eq!(imported.commitment(), identity.commitment());
+    }
+
+    #[test]
+    fn test_identity_from_json_invalid_private_key() {
+        let missing = serde_json::json!({ "hashingAlgorithm": "blake1" });
+        assert!(matches!(Identity::from_json(&missing), Err(SemaphoreError::InvalidParameter(_))));
+
+        let not_string = serde_json::json!({ "privateKey": 42 });
+        assert!(matches!(Identity::from_json(&not_string), Err(SemaphoreError::InvalidParameter(_))));
+
+        let not_base64 = serde_json::json!({ "privateKey": "not base64!" });
+        assert!(matches!(Identity::from_json(&not_base64), Err(SemaphoreError::InvalidPrivateKey(_))));
+
+        let bad_algorithm = serde_json::json!({ "privateKey": "c2VjcmV0", "hashingAlgorithm": "sha256" });
+        assert!(Identity::from_json(&bad_algorithm).is_err());
+    }
+
+    #[test]
+    fn test_identity_debug_redacts_secrets() {
+        let identity = Identity::new(Some(vec![7u8; 32])).unwrap();
+        let debug = format!("{:?}", identity);
+        assert!(debug.contains("<redacted 32 bytes>"));
+        assert!(!debug.contains(&format!("{:?}", identity.private_key())));
+        assert!(!debug.contains(&identity.secret_scalar().to_string()));
+        assert!(debug.contains(&identity.commitment().to_string()));
+        assert!(debug.contains(&identity.public_key().0.to_string()));
+    }
+
+    #[test]
+    fn test_identity_from_secret_scalar() {
+        let identity = Identity::new(Some(b"scalar".to_vec())).unwrap();
+        let from_scalar = Identity::from_secret_scalar(identity.secret_scalar()).unwrap();
+
+        assert_eq!(from_scalar.commitment(), identity.commitment());
+        assert_eq!(from_scalar.public_key(), identity.public_key());
+        assert!(from_scalar.private_key().is_empty());
+        assert!(from_scalar.sign_message(&[1u8; 32]).is_err());
+
+        let hex = from_scalar.secret_scalar_hex();
+        assert_eq!(hex.len(), 64);
+        assert_eq!(BigInt::parse_bytes(hex.as_bytes(), 16).unwrap(), *identity.secret_scalar());
+
+        assert!(Identity::from_secret_scalar(&BigInt::from(0)).is_err());
+        assert!(Identity::from_secret_scalar(&SUBORDER).is_err());
+    }
+
+    #[test]
+    fn test_derive_child() {
+        let master = Identity::new(Some(b"master".to_vec())).unwrap();
+        let child = master.derive_child(0).unwrap();
+        let same = master.derive_child(0).unwrap();
+        let sibling = master.derive_child(1).unwrap();
+
+        assert_eq!(child.private_key(), same.private_key());
+        assert_eq!(child.commitment(), same.commitment());
+        assert_ne!(child.commitment(), sibling.commitment());
+        assert_ne!(child.commitment(), master.commitment());
+    }
+
+    #[test]
+    fn test_viewing_key() {
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let viewing_key = identity.viewing_key();
+        assert_eq!(viewing_key, Identity::new(Some(b"secret".to_vec())).unwrap().viewing_key());
+        assert_eq!(viewing_key.to_string(), "1751831975893002364110484326414581552495248669567304911066692561476669486211");
+        assert_ne!(&viewing_key, identity.secret_scalar());
+        assert!(viewing_key < *SUBORDER);
+        assert_ne!(viewing_key, Identity::new(Some(b"other".to_vec())).unwrap().viewing_key());
+
+        let scalar_only = Identity::from_secret_scalar(identity.secret_scalar()).unwrap();
+        let scalar_viewing_key = scalar_only.viewing_key();
+        assert_eq!(scalar_viewing_key, Identity::from_secret_scalar(identity.secret_scalar()).unwrap().viewing_key());
+        assert_ne!(scalar_viewing_key, viewing_key);
+        assert_ne!(&scalar_viewing_key, scalar_only.secret_scalar());
+    }
+
+    #[test]
+    fn test_commitment_bytes_round_trip() {
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let key = identity.commitment_bytes();
+        assert_eq!(key.to_vec(), be_bigint_to_bytes(identity.commitment(), Some(32)).unwrap());
+        assert_eq!(&commitment_from_bytes(&key), identity.commitment());
+        assert_eq!(key, Identity::new(Some(b"secret".to_vec())).unwrap().commitment_bytes());
+        assert_ne!(key, Identity::new(Some(b"other".to_vec())).unwrap().commitment_bytes());
+
+        let mut small = [0u8; 32];
+        small[31] = 7;
+        assert_eq!(commitment_from_bytes(&small), BigInt::from(7));
+    }
+
+    #[test]
+    fn test_matches_with_algorithm() {
+        let identity = Identity::new(Some(b"secret".to_vec())).unwrap();
+        let blake1 = SupportedHashingAlgorithms::Blake1;
+        assert!(Identity::matches_with_algorithm(b"secret", identity.commitment(), blake1));
+        assert!(!Identity::matches_with_algorithm(b"other", identity.commitment(), blake1));
+        assert!(!Identity::matches_with_algorithm(&[], identity.commitment(), blake1));
+
+        // A commitment derived another way, here without clamping, does not match.
+        let legacy = Identity::with_derivation_mode(Some(b"secret".to_vec()), DerivationMode::Legacy).unwrap();
+        assert!(!Identity::matches_with_algorithm(b"secret", legacy.commitment(), blake1));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let identity = Identity::new(Some(b"fingerprint".to_vec())).unwrap();
+        let again = Identity::new(Some(b"fingerprint".to_vec())).unwrap();
+        let other = Identity::new(Some(b"other".to_vec())).unwrap();
+
+        assert_eq!(identity.fingerprint(), again.fingerprint());
+        assert_eq!(identity.fingerprint().len(), 13);
+        assert_ne!(identity.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn test_find_commitment() {
+        let identities: Vec<Identity> = [b"alice".to_vec(), b"bob".to_vec(), b"carol".to_vec()]
+            .into_iter()
+            .map(|key| Identity::new(Some(key)).unwrap())
+            .collect();
+        let keys: Vec<Point> = identities.iter().map(|i| i.public_key().clone()).collect();
+
+        assert_eq!(find_commitment(identities[1].commitment(), &keys), Some(1));
+        let absent = Identity::new(Some(b"dave".to_vec())).unwrap();
+        assert_eq!(find_commitment(absent.commitment(), &keys), None);
+
+        assert!(identities[0].commitment_collides_with(&identities[0].clone()));
+        assert!(!identities[0].commitment_collides_with(&identities[1]));
+    }
+
+    #[test]
+    fn test_poseidon2_errors_on_bad_input() {
+        let over_arity = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        assert!(matches!(poseidon2(over_arity), Err(SemaphoreError::Poseidon(_))));
+        let not_a_number = vec!["1".to_string(), "x".to_string()];
+        assert!(matches!(poseidon2(not_a_number), Err(SemaphoreError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_commitment_from_coords() {
+        let identity = Identity::new(Some(b"coords".to_vec())).unwrap();
+        let (x, y) = identity.public_key();
+        let commitment = Identity::commitment_from_coords(&x.to_string(), &y.to_string()).unwrap();
+        assert_eq!(&commitment, identity.commitment());
+
+        assert!(matches!(
+            Identity::commitment_from_coords("0", "3"),
+            Err(SemaphoreError::InvalidPublicKey(_))
+        ));
+        assert!(matches!(
+            Identity::commitment_from_coords("abc", "3"),
+            Err(SemaphoreError::InvalidParameter(_))
+        ));
+    }
+
     #[test]
     fn test_commitment_generation() {
         let identity = Identity::new(Some(b"commit test".to_vec())).unwrap();
-        let c = Identity::generate_commitment(&identity.public_key());
+        let c = Identity::generate_commitment(identity.public_key()).unwrap();
         assert_eq!(c, identity.commitment().clone());
     }
 } 
\ No newline at end of file
@@ -0,0 +1,59 @@
+use crate::Identity;
+use eddsa_poseidon::{pack_public_key, pack_signature};
+use serde_json::{json, Value};
+use utils::conversions::{buffer_to_hex, le_bigint_to_bytes};
+
+/// Generates a deterministic cross-library test vector for `private_key` and `message`.
+///
+/// All integers are decimal strings and all byte strings are lowercase hex:
+/// `privateKey`, `message`, `secretScalar`, `publicKey` (`[x, y]`), `commitment`,
+/// `packedPublicKey` (32 bytes, little-endian), `signature` (`Signature::to_json`) and
+/// `packedSignature` (64 bytes).
+///
+/// # Panics
+/// Panics if the key or message is rejected, since vectors are built from known-good inputs.
+pub fn generate_vector(private_key: &[u8], message: &[u8]) -> Value {
+    let identity = Identity::new(Some(private_key.to_vec())).expect("valid private key");
+    let signature = identity.sign_message(message).expect("message can be signed");
+    let packed_public_key = pack_public_key(identity.public_key()).expect("public key can be packed");
+    let packed_signature = pack_signature(&signature).expect("signature can be packed");
+
+    json!({
+        "privateKey": buffer_to_hex(private_key),
+        "message": buffer_to_hex(message),
+        "secretScalar": identity.secret_scalar().to_string(),
+        "publicKey": [identity.public_key().0.to_string(), identity.public_key().1.to_string()],
+        "commitment": identity.commitment().to_string(),
+        "packedPublicKey": buffer_to_hex(&le_bigint_to_bytes(&packed_public_key, Some(32)).expect("packed key fits")),
+        "signature": signature.to_json(),
+        "packedSignature": buffer_to_hex(&packed_signature),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_vector_shape() {
+        let vector = generate_vector(b"secret", &[2u8; 32]);
+        let keys: Vec<&str> = vector.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "privateKey",
+                "message",
+                "secretScalar",
+                "publicKey",
+                "commitment",
+                "packedPublicKey",
+                "signature",
+                "packedSignature"
+            ]
+        );
+        assert_eq!(vector["privateKey"], "736563726574");
+        assert_eq!(vector["packedSignature"].as_str().unwrap().len(), 128);
+        assert!(vector["signature"]["R8"].is_array() && vector["signature"]["S"].is_string());
+        assert_eq!(generate_vector(b"secret", &[2u8; 32]), vector);
+    }
+}
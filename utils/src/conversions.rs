@@ -1,11 +1,12 @@
 use base64::{engine::general_purpose, Engine as _};
 use hex::{decode as hex_decode, encode as hex_encode};
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
+use serde_json::{json, Value};
 
 /// Converts a BigInt to a hexadecimal string (without "0x" prefix)
 pub fn bigint_to_hex(value: &BigInt) -> String {
     let mut hex = value.to_str_radix(16);
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         hex.insert(0, '0');
     }
     hex
@@ -26,38 +27,109 @@ pub fn hex_to_big_int(value: &str) -> Result<BigInt, String> {
     }
 }
 
-/// Converts a byte slice to a BigInt (big-endian)
-pub fn be_bytes_to_bigint(bytes: &[u8]) -> BigInt {
-    BigInt::parse_bytes(hex_encode(bytes).as_bytes(), 16).unwrap()
+/// Parses a decimal string, or a hexadecimal one when it starts with `0x`/`0X`
+pub fn parse_numeric(s: &str) -> Result<BigInt, String> {
+    if s.starts_with("0x") || s.starts_with("0X") {
+        return hex_to_big_int(s);
+    }
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("'{}' is not a decimal or 0x-prefixed hexadecimal number", s));
+    }
+    BigInt::parse_bytes(s.as_bytes(), 10).ok_or_else(|| format!("'{}' is not a decimal number", s))
 }
 
-/// Converts a byte slice to a BigInt (little-endian)
-pub fn le_bytes_to_bigint(bytes: &[u8]) -> BigInt {
-    BigInt::parse_bytes(hex_encode(bytes.iter().rev().cloned().collect::<Vec<u8>>()).as_bytes(), 16).unwrap()
+/// Byte order used when converting between byte slices and BigInts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
 }
 
-/// Converts a BigInt to bytes (big-endian) with optional size padding
-pub fn be_bigint_to_bytes(value: &BigInt, size: Option<usize>) -> Result<Vec<u8>, String> {
-    let mut hex = bigint_to_hex(value);
-    let min_size = (hex.len() + 1) / 2;
+/// Converts a byte slice to a BigInt using the given byte order
+pub fn bytes_to_bigint(bytes: &[u8], endianness: Endianness) -> BigInt {
+    match endianness {
+        Endianness::Big => BigInt::parse_bytes(hex_encode(bytes).as_bytes(), 16).unwrap(),
+        Endianness::Little => {
+            BigInt::parse_bytes(hex_encode(bytes.iter().rev().cloned().collect::<Vec<u8>>()).as_bytes(), 16).unwrap()
+        }
+    }
+}
+
+/// Converts a BigInt to bytes using the given byte order, with optional size padding
+pub fn bigint_to_bytes(value: &BigInt, size: Option<usize>, endianness: Endianness) -> Result<Vec<u8>, String> {
+    if value.sign() == Sign::Minus {
+        return Err("Negative values cannot be converted to bytes".to_string());
+    }
+
+    let (_, mut buf) = value.to_bytes_be();
+    let min_size = buf.len();
     let size = size.unwrap_or(min_size);
 
     if size < min_size {
         return Err(format!("Size {} is too small, need at least {} bytes", size, min_size));
     }
 
-    while hex.len() < size * 2 {
-        hex.insert(0, '0');
+    // Left-pad in big-endian order, then flip for little-endian.
+    buf.splice(0..0, std::iter::repeat_n(0u8, size - min_size));
+    if endianness == Endianness::Little {
+        buf.reverse();
     }
+    Ok(buf)
+}
 
-    hex_decode(hex).map_err(|e| e.to_string())
+/// Converts a byte slice to a BigInt (big-endian)
+pub fn be_bytes_to_bigint(bytes: &[u8]) -> BigInt {
+    bytes_to_bigint(bytes, Endianness::Big)
+}
+
+/// Converts a byte slice to a BigInt (little-endian)
+pub fn le_bytes_to_bigint(bytes: &[u8]) -> BigInt {
+    bytes_to_bigint(bytes, Endianness::Little)
+}
+
+/// Converts a BigInt to bytes (big-endian) with optional size padding
+pub fn be_bigint_to_bytes(value: &BigInt, size: Option<usize>) -> Result<Vec<u8>, String> {
+    bigint_to_bytes(value, size, Endianness::Big)
 }
 
 /// Converts a BigInt to bytes (little-endian) with optional size padding
 pub fn le_bigint_to_bytes(value: &BigInt, size: Option<usize>) -> Result<Vec<u8>, String> {
-    let mut buf = be_bigint_to_bytes(value, size)?;
-    buf.reverse();
-    Ok(buf)
+    bigint_to_bytes(value, size, Endianness::Little)
+}
+
+/// Order of the BN254 scalar field, the field Baby Jubjub coordinates live in, as 32
+/// big-endian bytes.
+const FIELD_ORDER_BE: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Converts a field element to exactly 32 little-endian bytes, whatever its magnitude.
+/// Unlike `le_bigint_to_bytes(value, None)`, small values are zero-padded instead of being
+/// emitted at their minimal length; negative values and values not below the BN254 scalar
+/// field order are an error.
+pub fn le_bigint_to_bytes_field(value: &BigInt) -> Result<Vec<u8>, String> {
+    let bytes = le_bigint_to_bytes(value, Some(32))?;
+    if bytes.iter().rev().ge(FIELD_ORDER_BE.iter()) {
+        return Err("Value is not a field element".to_string());
+    }
+    Ok(bytes)
+}
+
+/// Converts a field element to a lowercase, zero-padded 64-character hexadecimal string (without "0x" prefix)
+pub fn field_to_hex32(value: &BigInt) -> Result<String, String> {
+    let bytes = be_bigint_to_bytes(value, Some(32))?;
+    Ok(hex_encode(bytes))
+}
+
+/// Converts a 64-character hexadecimal string (with or without "0x") to a field element
+pub fn field_from_hex32(value: &str) -> Result<BigInt, String> {
+    let hex = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+    if hex.len() != 64 {
+        return Err(format!("Expected 64 hexadecimal characters, got {}", hex.len()));
+    }
+    hex_to_big_int(hex)
 }
 
 /// Converts a byte slice to a hexadecimal string
@@ -67,7 +139,7 @@ pub fn buffer_to_hex(buffer: &[u8]) -> String {
 
 /// Converts a hexadecimal string to a byte buffer
 pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    let clean = if hex.len() % 2 != 0 {
+    let clean = if !hex.len().is_multiple_of(2) {
         format!("0{}", hex)
     } else {
         hex.to_string()
@@ -76,6 +148,51 @@ pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
     hex_decode(clean).map_err(|_| "Invalid hexadecimal string".to_string())
 }
 
+/// Converts a byte slice to an unpadded RFC 4648 base32 string
+pub fn buffer_to_base32(buffer: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut out = String::with_capacity((buffer.len() * 8).div_ceil(5));
+    let mut acc: u16 = 0;
+    let mut bits = 0;
+
+    for &byte in buffer {
+        acc = (acc << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((acc >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((acc << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Parses a Node.js JSON-serialized Buffer (`{"type":"Buffer","data":[...]}`) into bytes
+pub fn buffer_from_node_json(value: &Value) -> Result<Vec<u8>, String> {
+    if value.get("type").and_then(Value::as_str) != Some("Buffer") {
+        return Err("Expected an object with \"type\": \"Buffer\"".to_string());
+    }
+    let data = value
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Expected a \"data\" array".to_string())?;
+
+    data.iter()
+        .map(|v| {
+            v.as_u64()
+                .and_then(|n| u8::try_from(n).ok())
+                .ok_or_else(|| format!("Invalid byte value: {}", v))
+        })
+        .collect()
+}
+
+/// Converts bytes to the Node.js JSON serialization of a Buffer
+pub fn buffer_to_node_json(buffer: &[u8]) -> Value {
+    json!({ "type": "Buffer", "data": buffer })
+}
+
 /// Converts a byte slice to base64 string
 pub fn buffer_to_base64(buffer: &[u8]) -> String {
     general_purpose::STANDARD.encode(buffer)
@@ -86,6 +203,50 @@ pub fn base64_to_buffer(value: &str) -> Result<Vec<u8>, String> {
     general_purpose::STANDARD.decode(value).map_err(|e| e.to_string())
 }
 
+/// Type tag of `encode_key` for keys that are not valid UTF-8
+const KEY_TAG_BINARY: u8 = 0;
+/// Type tag of `encode_key` for keys that are valid UTF-8 text
+const KEY_TAG_TEXT: u8 = 1;
+
+/// Encodes a private key of any length as base64 of `tag || length || key`, with a one-byte
+/// tag (1 for UTF-8 text, 0 for binary) and the length as a 4-byte big-endian integer
+pub fn encode_key(key: &[u8]) -> String {
+    buffer_to_base64(&encode_key_bytes(key))
+}
+
+/// Like `encode_key`, returning the `tag || length || key` bytes without the base64 encoding
+pub fn encode_key_bytes(key: &[u8]) -> Vec<u8> {
+    let tag = if std::str::from_utf8(key).is_ok() { KEY_TAG_TEXT } else { KEY_TAG_BINARY };
+    let length = u32::try_from(key.len()).expect("Key length fits in 32 bits");
+    let mut payload = Vec::with_capacity(5 + key.len());
+    payload.push(tag);
+    payload.extend_from_slice(&length.to_be_bytes());
+    payload.extend_from_slice(key);
+    payload
+}
+
+/// Decodes a key encoded by `encode_key`, checking the tag, the length prefix and that text keys are UTF-8
+pub fn decode_key(value: &str) -> Result<Vec<u8>, String> {
+    decode_key_bytes(&base64_to_buffer(value)?)
+}
+
+/// Like `decode_key`, reading the `tag || length || key` bytes of `encode_key_bytes` directly
+pub fn decode_key_bytes(payload: &[u8]) -> Result<Vec<u8>, String> {
+    if payload.len() < 5 {
+        return Err(format!("Encoded key must be at least 5 bytes, got {}", payload.len()));
+    }
+    let (header, key) = payload.split_at(5);
+    let length = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if key.len() != length {
+        return Err(format!("Encoded key length is {} but {} bytes follow", length, key.len()));
+    }
+    match header[0] {
+        KEY_TAG_TEXT if std::str::from_utf8(key).is_err() => Err("Text key is not valid UTF-8".to_string()),
+        KEY_TAG_TEXT | KEY_TAG_BINARY => Ok(key.to_vec()),
+        tag => Err(format!("Unknown key type tag {}", tag)),
+    }
+}
+
 /// Converts UTF-8 text to base64
 pub fn text_to_base64(value: &str) -> String {
     general_purpose::STANDARD.encode(value.as_bytes())
@@ -140,6 +301,49 @@ mod tests {
         assert_eq!(buffer, back_be);
     }
 
+    #[test]
+    fn test_generic_endianness_matches_specific_functions() {
+        let buffer: Vec<u8> = vec![0x01, 0x02, 0x03, 0x04];
+        assert_eq!(bytes_to_bigint(&buffer, Endianness::Big), be_bytes_to_bigint(&buffer));
+        assert_eq!(bytes_to_bigint(&buffer, Endianness::Big), BigInt::from(0x01020304));
+        assert_eq!(bytes_to_bigint(&buffer, Endianness::Little), le_bytes_to_bigint(&buffer));
+        assert_eq!(bytes_to_bigint(&buffer, Endianness::Little), BigInt::from(0x04030201));
+
+        let value = BigInt::from(0x0102);
+        assert_eq!(bigint_to_bytes(&value, Some(4), Endianness::Big).unwrap(), vec![0, 0, 1, 2]);
+        assert_eq!(bigint_to_bytes(&value, Some(4), Endianness::Little).unwrap(), vec![2, 1, 0, 0]);
+        assert_eq!(bigint_to_bytes(&value, None, Endianness::Big).unwrap(), be_bigint_to_bytes(&value, None).unwrap());
+        assert_eq!(bigint_to_bytes(&value, None, Endianness::Little).unwrap(), le_bigint_to_bytes(&value, None).unwrap());
+        assert!(bigint_to_bytes(&value, Some(1), Endianness::Big).is_err());
+    }
+
+    #[test]
+    fn test_field_to_and_from_hex32() {
+        let zero = field_to_hex32(&BigInt::from(0)).unwrap();
+        assert_eq!(zero, "0".repeat(64));
+        assert_eq!(field_from_hex32(&zero).unwrap(), BigInt::from(0));
+
+        let one = field_to_hex32(&BigInt::from(1)).unwrap();
+        assert_eq!(one, format!("{}1", "0".repeat(63)));
+        assert_eq!(field_from_hex32(&one).unwrap(), BigInt::from(1));
+
+        let r_minus_one = BigInt::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495616",
+            10,
+        )
+        .unwrap();
+        let hex = field_to_hex32(&r_minus_one).unwrap();
+        assert_eq!(hex, "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000");
+        assert_eq!(field_from_hex32(&hex).unwrap(), r_minus_one);
+    }
+
+    #[test]
+    fn test_field_hex32_errors() {
+        let too_large = BigInt::from(1) << 256u32;
+        assert!(field_to_hex32(&too_large).is_err());
+        assert!(field_from_hex32("0x01").is_err());
+    }
+
     #[test]
     fn test_buffer_to_and_from_hexadecimal() {
         let hex = "deadbeef";
@@ -148,6 +352,32 @@ mod tests {
         assert_eq!(hex_roundtrip, hex);
     }
 
+    #[test]
+    fn test_buffer_to_base32() {
+        assert_eq!(buffer_to_base32(b""), "");
+        assert_eq!(buffer_to_base32(b"f"), "MY");
+        assert_eq!(buffer_to_base32(b"foo"), "MZXW6");
+        assert_eq!(buffer_to_base32(b"foobar"), "MZXW6YTBOI");
+    }
+
+    #[test]
+    fn test_node_buffer_json_roundtrip() {
+        let key: Vec<u8> = (0..32).map(|i| i * 8).collect();
+        let json = buffer_to_node_json(&key);
+        assert_eq!(json["type"], "Buffer");
+        assert_eq!(json["data"].as_array().unwrap().len(), 32);
+        assert_eq!(buffer_from_node_json(&json).unwrap(), key);
+    }
+
+    #[test]
+    fn test_node_buffer_json_malformed() {
+        assert!(buffer_from_node_json(&serde_json::json!({ "data": [1, 2] })).is_err());
+        assert!(buffer_from_node_json(&serde_json::json!({ "type": "Buffer" })).is_err());
+        assert!(buffer_from_node_json(&serde_json::json!({ "type": "Buffer", "data": [1, 256] })).is_err());
+        assert!(buffer_from_node_json(&serde_json::json!({ "type": "Buffer", "data": [-1] })).is_err());
+        assert!(buffer_from_node_json(&serde_json::json!({ "type": "Buffer", "data": ["1"] })).is_err());
+    }
+
     #[test]
     fn test_base64_text_roundtrip() {
         let text = "Hello, World!";
@@ -164,15 +394,122 @@ mod tests {
         assert_eq!(data.to_vec(), decoded);
     }
 
+    #[test]
+    fn test_parse_numeric() {
+        assert_eq!(parse_numeric("123").unwrap(), BigInt::from(123));
+        assert_eq!(parse_numeric("0x7b").unwrap(), BigInt::from(123));
+        assert_eq!(parse_numeric("0X7B").unwrap(), BigInt::from(123));
+        assert_eq!(parse_numeric("-5").unwrap(), BigInt::from(-5));
+        assert!(parse_numeric("12z").is_err());
+        assert!(parse_numeric("7b").is_err());
+        assert!(parse_numeric("+1").is_err());
+        assert!(parse_numeric("").is_err());
+        assert!(parse_numeric("0x").is_err());
+    }
+
     #[test]
     fn test_invalid_hex_to_bigint() {
         assert!(hex_to_big_int("thisisnothex").is_err());
     }
 
+    /// The previous hex-string based implementation, kept as a reference.
+    fn hex_bigint_to_bytes(value: &BigInt, size: Option<usize>, endianness: Endianness) -> Result<Vec<u8>, String> {
+        let mut hex = bigint_to_hex(value);
+        let min_size = hex.len().div_ceil(2);
+        let size = size.unwrap_or(min_size);
+        if size < min_size {
+            return Err(format!("Size {} is too small, need at least {} bytes", size, min_size));
+        }
+        while hex.len() < size * 2 {
+            hex.insert(0, '0');
+        }
+        let mut buf = hex_decode(hex).map_err(|e| e.to_string())?;
+        if endianness == Endianness::Little {
+            buf.reverse();
+        }
+        Ok(buf)
+    }
+
+    #[test]
+    fn test_bigint_to_bytes_matches_hex_implementation() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        for _ in 0..500 {
+            let len = rng.random_range(1..48);
+            let bytes: Vec<u8> = (0..len).map(|_| rng.random()).collect();
+            let value = be_bytes_to_bigint(&bytes);
+            let size = match rng.random_range(0..3) {
+                0 => None,
+                _ => Some(rng.random_range(0..48)),
+            };
+            for endianness in [Endianness::Big, Endianness::Little] {
+                assert_eq!(
+                    bigint_to_bytes(&value, size, endianness),
+                    hex_bigint_to_bytes(&value, size, endianness),
+                    "value {} size {:?}",
+                    value,
+                    size
+                );
+            }
+        }
+        assert_eq!(be_bigint_to_bytes(&BigInt::from(0), None).unwrap(), vec![0]);
+        assert!(be_bigint_to_bytes(&BigInt::from(-1), Some(32)).is_err());
+    }
+
     #[test]
     fn test_le_bigint_padding_error() {
         let value = BigInt::from(123456u64);
         let result = le_bigint_to_bytes(&value, Some(1));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_le_bigint_to_bytes_field() {
+        let small = le_bigint_to_bytes_field(&BigInt::from(1)).unwrap();
+        assert_eq!(small.len(), 32);
+        assert_eq!(small[0], 1);
+        assert!(small[1..].iter().all(|&b| b == 0));
+        assert_eq!(le_bigint_to_bytes_field(&BigInt::from(0)).unwrap(), vec![0u8; 32]);
+
+        let order = BigInt::from_bytes_be(Sign::Plus, &FIELD_ORDER_BE);
+        assert_eq!(
+            order.to_string(),
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+        );
+        let max = le_bigint_to_bytes_field(&(&order - 1)).unwrap();
+        assert_eq!(le_bytes_to_bigint(&max), &order - 1);
+        assert!(le_bigint_to_bytes_field(&order).is_err());
+        assert!(le_bigint_to_bytes_field(&(BigInt::from(1) << 256u32)).is_err());
+        assert!(le_bigint_to_bytes_field(&BigInt::from(-1)).is_err());
+    }
+
+    #[test]
+    fn test_encode_key_round_trip() {
+        let text = encode_key(b"secret");
+        assert_eq!(base64_to_buffer(&text).unwrap(), [&[1u8, 0, 0, 0, 6][..], b"secret"].concat());
+        assert_eq!(decode_key(&text).unwrap(), b"secret");
+
+        let binary: Vec<u8> = (0..32).map(|i| 0xff - i).collect();
+        let encoded = encode_key(&binary);
+        assert_eq!(base64_to_buffer(&encoded).unwrap()[..5], [0, 0, 0, 0, 32]);
+        assert_eq!(decode_key(&encoded).unwrap(), binary);
+
+        let empty = encode_key(&[]);
+        assert_eq!(base64_to_buffer(&empty).unwrap(), [1, 0, 0, 0, 0]);
+        assert_eq!(decode_key(&empty).unwrap(), Vec::<u8>::new());
+
+        assert_eq!(encode_key_bytes(b"secret"), base64_to_buffer(&text).unwrap());
+        assert_eq!(decode_key_bytes(&encode_key_bytes(&binary)).unwrap(), binary);
+        assert!(decode_key_bytes(&[1, 0, 0, 0, 2, b'a']).is_err());
+    }
+
+    #[test]
+    fn test_decode_key_rejects_malformed_input() {
+        assert!(decode_key(&buffer_to_base64(&[1, 0, 0])).is_err());
+        assert!(decode_key(&buffer_to_base64(&[1, 0, 0, 0, 2, b'a'])).is_err());
+        assert!(decode_key(&buffer_to_base64(&[2, 0, 0, 0, 0])).is_err());
+        assert!(decode_key(&buffer_to_base64(&[1, 0, 0, 0, 1, 0xff])).is_err());
+        assert!(decode_key("not base64!").is_err());
+    }
 }
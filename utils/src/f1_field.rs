@@ -1,5 +1,7 @@
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_traits::{One, Zero};
+use rand::RngCore;
+use std::ops::{Add, Mul, Neg, Sub};
 use crate::scalar;
 
 #[derive(Debug, Clone)]
@@ -9,6 +11,8 @@ pub struct F1Field {
     order: BigInt,
     half: BigInt,
     pub negone: BigInt,
+    /// `order - 1` when the order is a power of two, so reduction is a bitwise AND.
+    mask: Option<BigInt>,
 }
 
 impl F1Field {
@@ -17,6 +21,8 @@ impl F1Field {
         let zero = BigInt::zero();
         let half = &order >> 1;
         let negone = &order - &one;
+        let is_power_of_two = order > zero && (&order & &negone).is_zero();
+        let mask = is_power_of_two.then(|| negone.clone());
 
         Self {
             one,
@@ -24,10 +30,15 @@ impl F1Field {
             order,
             half,
             negone,
+            mask,
         }
     }
 
     pub fn e(&self, res: BigInt) -> BigInt {
+        if let Some(mask) = &self.mask {
+            // Two's complement AND also maps negative values into [0, order).
+            return res & mask;
+        }
         let r = res % &self.order;
         if r < self.zero {
             r + &self.order
@@ -37,7 +48,17 @@ impl F1Field {
     }
 
     pub fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
-        (a * b) % &self.order
+        self.rem(a * b)
+    }
+
+    /// `value % order`, with a bitwise AND when the order is a power of two. As with `%`, a
+    /// negative value gives a result in `(-order, 0]`.
+    fn rem(&self, value: BigInt) -> BigInt {
+        match &self.mask {
+            Some(mask) if value.sign() == Sign::Minus => -(-value & mask),
+            Some(mask) => value & mask,
+            None => value % &self.order,
+        }
     }
 
     pub fn sub(&self, a: &BigInt, b: &BigInt) -> BigInt {
@@ -95,7 +116,7 @@ impl F1Field {
     }
 
     pub fn square(&self, a: &BigInt) -> BigInt {
-        (a * a) % &self.order
+        self.rem(a * a)
     }
 
     pub fn lt(&self, a: &BigInt, b: &BigInt) -> bool {
@@ -138,6 +159,27 @@ impl F1Field {
         a.is_zero()
     }
 
+    /// Samples a uniformly random canonical field element.
+    /// Uses rejection sampling over the order's bit length to avoid modulo bias.
+    pub fn random<R: RngCore>(&self, rng: &mut R) -> BigInt {
+        let bit_len = self.order.bits() as usize;
+        let byte_len = bit_len.div_ceil(8);
+        let top_mask = match bit_len % 8 {
+            0 => 0xff,
+            bits => (1u8 << bits) - 1,
+        };
+        let mut buf = vec![0u8; byte_len];
+
+        loop {
+            rng.fill_bytes(&mut buf);
+            buf[0] &= top_mask;
+            let candidate = BigInt::from_bytes_be(Sign::Plus, &buf);
+            if candidate < self.order {
+                return candidate;
+            }
+        }
+    }
+
     pub fn pow(&self, mut base: BigInt, mut exp: BigInt) -> BigInt {
         if scalar::is_zero(&exp) {
             return self.one.clone();
@@ -166,6 +208,170 @@ impl F1Field {
     }
 }
 
+/// Arithmetic of a prime field, so curve code can be written once for any field backend.
+pub trait PrimeField {
+    fn order(&self) -> &BigInt;
+    fn zero(&self) -> BigInt;
+    fn one(&self) -> BigInt;
+    fn add(&self, a: &BigInt, b: &BigInt) -> BigInt;
+    fn sub(&self, a: &BigInt, b: &BigInt) -> BigInt;
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt;
+    fn div(&self, a: &BigInt, b: &BigInt) -> BigInt;
+    fn square(&self, a: &BigInt) -> BigInt;
+    fn neg(&self, a: &BigInt) -> BigInt;
+    fn inv(&self, a: &BigInt) -> BigInt;
+    fn pow(&self, base: &BigInt, exp: &BigInt) -> BigInt;
+}
+
+impl PrimeField for F1Field {
+    fn order(&self) -> &BigInt {
+        &self.order
+    }
+
+    fn zero(&self) -> BigInt {
+        self.zero.clone()
+    }
+
+    fn one(&self) -> BigInt {
+        self.one.clone()
+    }
+
+    fn add(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        F1Field::add(self, a, b)
+    }
+
+    fn sub(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        F1Field::sub(self, a, b)
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        F1Field::mul(self, a, b)
+    }
+
+    fn div(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        F1Field::div(self, a, b)
+    }
+
+    fn square(&self, a: &BigInt) -> BigInt {
+        F1Field::square(self, a)
+    }
+
+    fn neg(&self, a: &BigInt) -> BigInt {
+        F1Field::neg(self, a)
+    }
+
+    fn inv(&self, a: &BigInt) -> BigInt {
+        F1Field::inv(self, a)
+    }
+
+    fn pow(&self, base: &BigInt, exp: &BigInt) -> BigInt {
+        F1Field::pow(self, base.clone(), exp.clone())
+    }
+}
+
+/// An element of an `F1Field` with operator overloads, for code where readability matters more
+/// than avoiding clones. Hot paths should keep using the `F1Field` methods directly.
+#[derive(Debug, Clone)]
+pub struct FieldElement<'a> {
+    field: &'a F1Field,
+    value: BigInt,
+}
+
+impl<'a> FieldElement<'a> {
+    /// Wraps `value`, reduced into `[0, order)`.
+    pub fn new(field: &'a F1Field, value: BigInt) -> Self {
+        let value = field.e(value);
+        Self { field, value }
+    }
+
+    pub fn value(&self) -> &BigInt {
+        &self.value
+    }
+
+    pub fn into_value(self) -> BigInt {
+        self.value
+    }
+
+    fn check_same_field(&self, other: &Self) {
+        assert!(self.field.order == other.field.order, "field elements belong to different fields");
+    }
+}
+
+impl PartialEq for FieldElement<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.field.order == other.field.order && self.value == other.value
+    }
+}
+
+impl Eq for FieldElement<'_> {}
+
+impl<'a> Add for &FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn add(self, rhs: Self) -> FieldElement<'a> {
+        self.check_same_field(rhs);
+        FieldElement { field: self.field, value: self.field.add(&self.value, &rhs.value) }
+    }
+}
+
+impl<'a> Sub for &FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn sub(self, rhs: Self) -> FieldElement<'a> {
+        self.check_same_field(rhs);
+        FieldElement { field: self.field, value: self.field.sub(&self.value, &rhs.value) }
+    }
+}
+
+impl<'a> Mul for &FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn mul(self, rhs: Self) -> FieldElement<'a> {
+        self.check_same_field(rhs);
+        FieldElement { field: self.field, value: self.field.mul(&self.value, &rhs.value) }
+    }
+}
+
+impl<'a> Neg for &FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn neg(self) -> FieldElement<'a> {
+        FieldElement { field: self.field, value: self.field.neg(&self.value) }
+    }
+}
+
+impl<'a> Add for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn add(self, rhs: Self) -> FieldElement<'a> {
+        &self + &rhs
+    }
+}
+
+impl<'a> Sub for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn sub(self, rhs: Self) -> FieldElement<'a> {
+        &self - &rhs
+    }
+}
+
+impl<'a> Mul for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn mul(self, rhs: Self) -> FieldElement<'a> {
+        &self * &rhs
+    }
+}
+
+impl<'a> Neg for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn neg(self) -> FieldElement<'a> {
+        -&self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -316,6 +522,36 @@ mod tests {
         assert!(!f.is_zero(&b));
     }
 
+    #[test]
+    fn samples_random_elements_within_field() {
+        let f = field();
+        let mut rng = rand::rng();
+        let mut counts = [0usize; 13];
+
+        for _ in 0..10000 {
+            let value = f.random(&mut rng);
+            assert!(value >= f.zero && value < f.order);
+            counts[usize::try_from(value).unwrap()] += 1;
+        }
+
+        for count in counts {
+            assert!(count > 600 && count < 950, "skewed sample count {}", count);
+        }
+    }
+
+    #[test]
+    fn implements_prime_field_trait() {
+        let f = field();
+        let a = e(&f, 2);
+        let b = e(&f, 11);
+
+        assert_eq!(PrimeField::add(&f, &a, &b), f.add(&a, &b));
+        assert_eq!(PrimeField::sub(&f, &a, &b), f.sub(&a, &b));
+        assert_eq!(PrimeField::mul(&f, &a, &b), f.mul(&a, &b));
+        assert_eq!(PrimeField::inv(&f, &a), f.inv(&a));
+        assert_eq!(PrimeField::pow(&f, &a, &BigInt::from(3)), f.pow(a.clone(), BigInt::from(3)));
+    }
+
     #[test]
     fn exponentiates_within_field() {
         let f = field();
@@ -330,4 +566,55 @@ mod tests {
         assert_eq!(f.pow(c.clone(), BigInt::from(-1)), f.inv(&c));
         assert_eq!(f.pow(d.clone(), BigInt::from(-30)), BigInt::from(1));
     }
+
+    #[test]
+    fn field_element_operators_match_methods() {
+        let f = field();
+        for a in 0..13 {
+            for b in 0..13 {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                let fx = FieldElement::new(&f, x.clone());
+                let fy = FieldElement::new(&f, y.clone());
+
+                assert_eq!((&fx + &fy).value(), &f.add(&x, &y));
+                assert_eq!((&fx - &fy).value(), &f.sub(&x, &y));
+                assert_eq!((&fx * &fy).value(), &f.mul(&x, &y));
+                assert_eq!((-&fx).value(), &f.neg(&x));
+                assert_eq!((fx.clone() + fy.clone()) * fx.clone(), &(&fx * &fx) + &(&fy * &fx));
+            }
+        }
+        assert_eq!(FieldElement::new(&f, BigInt::from(-1)).into_value(), BigInt::from(12));
+    }
+
+    #[test]
+    #[should_panic(expected = "different fields")]
+    fn field_element_rejects_mixed_fields() {
+        let f = field();
+        let g = F1Field::new(BigInt::from(7));
+        let _ = FieldElement::new(&f, BigInt::from(1)) + FieldElement::new(&g, BigInt::from(1));
+    }
+
+    #[test]
+    fn power_of_two_order_reduces_by_masking() {
+        let f = F1Field::new(BigInt::from(16));
+        assert!(f.mask.is_some());
+        assert!(field().mask.is_none());
+        assert!(F1Field::new(BigInt::from(1)).mask.is_some());
+
+        let order = BigInt::from(16);
+        for a in -40..40 {
+            let a = BigInt::from(a);
+            assert_eq!(f.e(a.clone()), ((&a % &order) + &order) % &order);
+        }
+        for a in -40..40 {
+            let a = BigInt::from(a);
+            for b in -40..40 {
+                let b = BigInt::from(b);
+                assert_eq!(f.mul(&a, &b), (&a * &b) % &order);
+            }
+            assert_eq!(f.square(&a), (&a * &a) % &order);
+        }
+        // Negative products keep their sign, as with `%`.
+        assert_eq!(f.mul(&BigInt::from(-3), &BigInt::from(7)), BigInt::from(-5));
+    }
 }
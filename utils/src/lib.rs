@@ -2,3 +2,5 @@ pub mod scalar;
 pub mod f1_field;
 pub mod conversions;
 pub mod type_checks;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
@@ -1,11 +1,11 @@
-/// Scalar module
-/// This module provides utility functions for performing scalar operations
-/// within a field, especially designed to handle operations on big integers.
-/// The operations include scalar inversion, exponentiation, and modular reduction.
-/// Functions are implemented to ensure mathematical correctness and efficiency,
-/// supporting both positive and negative big integer values.
-
-use num_bigint::BigInt;
+//! Scalar module
+//! This module provides utility functions for performing scalar operations
+//! within a field, especially designed to handle operations on big integers.
+//! The operations include scalar inversion, exponentiation, and modular reduction.
+//! Functions are implemented to ensure mathematical correctness and efficiency,
+//! supporting both positive and negative big integer values.
+
+use num_bigint::{BigInt, Sign};
 use num_traits::{Zero, One, ToPrimitive};
 use std::ops::{Shr, Mul};
 
@@ -26,9 +26,11 @@ pub fn is_zero(a: &BigInt) -> bool {
 /// * `a` - The BigInt scalar value to check.
 /// 
 /// # Returns
-/// * `true` if `a` is odd, `false` if it is even.
+/// * `true` if `a` is odd, `false` if it is even. Negative values follow their magnitude,
+///   e.g. `-3` is odd, as with the two's complement `a & 1`.
 pub fn is_odd(a: &BigInt) -> bool {
-    (a & BigInt::one()) == BigInt::one()
+    // Reads the lowest bit in place; `a & 1` would allocate a BigInt on every call.
+    a.bit(0)
 }
 
 /// Performs a bitwise right shift on a BigInt scalar value.
@@ -39,11 +41,23 @@ pub fn is_odd(a: &BigInt) -> bool {
 /// * `n` - The number of bits to shift `a` by.
 /// 
 /// # Returns
-/// * The result of shifting `a` right by `n` bits.
+/// * The result of shifting `a` right by `n` bits. Shifting a non-negative
+///   value by at least its bit length yields zero, however large `n` is.
+///
+/// # Panics
+/// * If `n` is negative.
 pub fn shift_right(a: &BigInt, n: &BigInt) -> BigInt {
-    // Convert BigInt to usize for shifting
-    let n_usize = n.to_usize().expect("Shift amount too large");
-    a.shr(n_usize)
+    assert!(n.sign() != Sign::Minus, "Shift amount must be non-negative");
+
+    if a.sign() != Sign::Minus && n >= &BigInt::from(a.bits()) {
+        return BigInt::zero();
+    }
+
+    match n.to_usize() {
+        Some(n_usize) => a.shr(n_usize),
+        // Only reachable for negative `a`, which floors towards -1.
+        None => -BigInt::one(),
+    }
 }
 
 /// Multiplies two BigInt scalar values.
@@ -57,6 +71,31 @@ pub fn mul(a: &BigInt, b: &BigInt) -> BigInt {
     a.mul(b)
 }
 
+/// Raises a BigInt scalar value to a power, without any modular reduction.
+/// Uses exponentiation by squaring.
+///
+/// # Arguments
+/// * `base` - The BigInt value to exponentiate.
+/// * `exp` - The exponent.
+/// 
+/// # Returns
+/// * `base` raised to `exp`, with `pow(base, 0) == 1`.
+pub fn pow(base: &BigInt, exp: u32) -> BigInt {
+    let mut result = BigInt::one();
+    let mut square = base.clone();
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.mul(&square);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            square = square.clone().mul(&square);
+        }
+    }
+    result
+}
+
 /// Compares two BigInt scalar values to determine if the first is greater than the second.
 ///
 /// # Arguments
@@ -68,6 +107,31 @@ pub fn gt(a: &BigInt, b: &BigInt) -> bool {
     a > b
 }
 
+/// Reduces a BigInt scalar value into the canonical range `[0, m)`.
+/// Negative inputs are mapped to their non-negative representative.
+///
+/// # Arguments
+/// * `a` - The BigInt value to reduce.
+/// * `m` - The (positive) modulus.
+/// 
+/// # Returns
+/// * `a mod m` in the range `[0, m)`.
+pub fn reduce(a: &BigInt, m: &BigInt) -> BigInt {
+    ((a % m) + m) % m
+}
+
+/// Computes the modular negation of a BigInt scalar value.
+///
+/// # Arguments
+/// * `a` - The BigInt value to negate.
+/// * `m` - The (positive) modulus.
+/// 
+/// # Returns
+/// * `-a mod m` in the range `[0, m)`.
+pub fn neg_mod(a: &BigInt, m: &BigInt) -> BigInt {
+    (m - reduce(a, m)) % m
+}
+
 /// Converts a BigInt scalar value into an array of bits (as u8).
 /// Starting from least significant bit.
 ///
@@ -98,6 +162,18 @@ mod tests {
     use num_bigint::BigInt;
     use num_traits::{One, Zero};
 
+    #[test]
+    fn test_is_odd_matches_bitwise_and() {
+        for value in [-1_000_001i64, -4, -3, -2, -1, 0, 1, 2, 3, 1 << 40, (1 << 40) + 1] {
+            let value = BigInt::from(value);
+            assert_eq!(is_odd(&value), (&value & BigInt::one()) == BigInt::one(), "{}", value);
+        }
+        assert!(is_odd(&BigInt::from(-3)));
+        assert!(!is_odd(&BigInt::from(-4)));
+        assert!(is_odd(&((BigInt::one() << 300u32) + 1)));
+        assert!(!is_odd(&-(BigInt::one() << 300u32)));
+    }
+
     #[test]
     fn test_is_zero() {
         assert!(is_zero(&BigInt::zero()));
@@ -120,6 +196,21 @@ mod tests {
         assert_eq!(shift_right(&BigInt::from(1), &BigInt::from(1)), BigInt::from(0));
     }
 
+    #[test]
+    fn test_shift_right_by_huge_amount() {
+        let huge = BigInt::from(1) << 128u32;
+        assert_eq!(shift_right(&BigInt::from(12345), &huge), BigInt::zero());
+        assert_eq!(shift_right(&BigInt::from(12345), &BigInt::from(14)), BigInt::zero());
+        assert_eq!(shift_right(&BigInt::zero(), &huge), BigInt::zero());
+        assert_eq!(shift_right(&BigInt::from(-5), &huge), BigInt::from(-1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shift amount must be non-negative")]
+    fn test_shift_right_negative_amount() {
+        let _ = shift_right(&BigInt::from(8), &BigInt::from(-1));
+    }
+
     #[test]
     fn test_mul() {
         assert_eq!(mul(&BigInt::from(2), &BigInt::from(3)), BigInt::from(6));
@@ -127,6 +218,16 @@ mod tests {
         assert_eq!(mul(&BigInt::zero(), &BigInt::from(1000)), BigInt::zero());
     }
 
+    #[test]
+    fn test_pow() {
+        assert_eq!(pow(&BigInt::from(2), 10), BigInt::from(1024));
+        assert_eq!(pow(&BigInt::from(3), 0), BigInt::one());
+        assert_eq!(pow(&BigInt::zero(), 0), BigInt::one());
+        assert_eq!(pow(&BigInt::from(-2), 3), BigInt::from(-8));
+        assert_eq!(pow(&BigInt::from(2), 300), BigInt::one() << 300);
+        assert_eq!(pow(&BigInt::from(10), 77), format!("1{}", "0".repeat(77)).parse::<BigInt>().unwrap());
+    }
+
     #[test]
     fn test_gt() {
         assert!(gt(&BigInt::from(5), &BigInt::from(2)));
@@ -134,6 +235,25 @@ mod tests {
         assert!(!gt(&BigInt::from(2), &BigInt::from(2)));
     }
 
+    #[test]
+    fn test_reduce() {
+        let m = BigInt::from(13);
+        assert_eq!(reduce(&BigInt::from(5), &m), BigInt::from(5));
+        assert_eq!(reduce(&BigInt::from(30), &m), BigInt::from(4));
+        assert_eq!(reduce(&BigInt::from(-2), &m), BigInt::from(11));
+        assert_eq!(reduce(&BigInt::from(-26), &m), BigInt::zero());
+    }
+
+    #[test]
+    fn test_neg_mod() {
+        let m = BigInt::from(13);
+        assert_eq!(neg_mod(&BigInt::zero(), &m), BigInt::zero());
+        assert_eq!(neg_mod(&BigInt::from(2), &m), BigInt::from(11));
+        assert_eq!(neg_mod(&BigInt::from(15), &m), BigInt::from(11));
+        assert_eq!(neg_mod(&BigInt::from(-3), &m), BigInt::from(3));
+        assert_eq!(neg_mod(&BigInt::from(26), &m), BigInt::zero());
+    }
+
     #[test]
     fn test_bits() {
         assert_eq!(bits(&BigInt::from(0)), Vec::<u8>::new());
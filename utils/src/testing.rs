@@ -0,0 +1,52 @@
+use crate::conversions::le_bigint_to_bytes;
+use num_bigint::BigInt;
+
+/// Asserts that two field elements are equal both as values and as canonical
+/// 32-byte little-endian encodings, printing both forms on mismatch.
+/// Values that do not fit in 32 bytes always fail.
+#[track_caller]
+pub fn assert_field_eq(a: &BigInt, b: &BigInt) {
+    let a_bytes = le_bigint_to_bytes(a, Some(32));
+    let b_bytes = le_bigint_to_bytes(b, Some(32));
+
+    if a != b || a_bytes.is_err() || a_bytes != b_bytes {
+        panic!(
+            "field elements differ\n  left:  {}\n         le32 = {}\n  right: {}\n         le32 = {}",
+            a,
+            encoding(&a_bytes),
+            b,
+            encoding(&b_bytes)
+        );
+    }
+}
+
+fn encoding(bytes: &Result<Vec<u8>, String>) -> String {
+    match bytes {
+        Ok(bytes) => hex::encode(bytes),
+        Err(e) => format!("<not encodable: {}>", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_field_eq_passes_for_equal_values() {
+        assert_field_eq(&BigInt::from(0), &BigInt::from(0));
+        assert_field_eq(&BigInt::from(123456789), &BigInt::from(123456789));
+    }
+
+    #[test]
+    #[should_panic(expected = "field elements differ")]
+    fn test_assert_field_eq_fails_for_different_values() {
+        assert_field_eq(&BigInt::from(1), &BigInt::from(256));
+    }
+
+    #[test]
+    #[should_panic(expected = "not encodable")]
+    fn test_assert_field_eq_fails_for_unencodable_values() {
+        let too_large = BigInt::from(1) << 256u32;
+        assert_field_eq(&too_large, &too_large);
+    }
+}
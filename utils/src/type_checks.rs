@@ -1,5 +1,6 @@
 // src/type_checks.rs
 use num_bigint::BigInt;
+use crate::conversions::parse_numeric;
 
 /// Enum representing supported types. Used for type checking.
 #[derive(Debug, PartialEq)]
@@ -24,7 +25,7 @@ pub fn is_defined<T>(value: &Option<T>) -> bool {
     value.is_some()
 }
 
-/// Rust handles types statically, so these are example value checks for dynamic contexts.
+// Rust handles types statically, so these are example value checks for dynamic contexts.
 
 pub fn is_number(value: &serde_json::Value) -> bool {
     value.is_number()
@@ -68,22 +69,36 @@ pub fn is_stringified_bigint(value: &serde_json::Value) -> bool {
     is_bigint(value)
 }
 
+/// Checks if a JSON value is a hexadecimal string.
+///
+/// # Arguments
+/// * `value` - The value to check.
+/// * `prefix` - Whether the string must start with `0x` (or `0X`).
+///
+/// # Returns
+/// `true` if the string has at least one hex digit after the optional prefix and nothing else,
+/// so `""` and a bare `"0x"` are rejected.
 pub fn is_hexadecimal(value: &serde_json::Value, prefix: bool) -> bool {
-    if let Some(s) = value.as_str() {
-        if prefix {
-            s.starts_with("0x") || s.starts_with("0X") && s[2..].chars().all(|c| c.is_digit(16))
-        } else {
-            s.chars().all(|c| c.is_digit(16))
+    let Some(s) = value.as_str() else {
+        return false;
+    };
+    let digits = if prefix {
+        match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) => digits,
+            None => return false,
         }
     } else {
-        false
-    }
+        s
+    };
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 pub fn is_big_number(value: &serde_json::Value) -> bool {
     is_bigint(value) || is_stringified_bigint(value)
 }
 
+/// Checks whether a value looks like a big number. Any JSON number passes, including ones
+/// above `2^53` that have already lost precision; use `to_bigint` to convert safely.
 pub fn is_big_numberish(value: &serde_json::Value) -> bool {
     is_number(value)
         || is_bigint(value)
@@ -93,6 +108,35 @@ pub fn is_big_numberish(value: &serde_json::Value) -> bool {
         || is_uint8_array(value)
 }
 
+/// Largest integer a JSON number (an IEEE 754 double) represents exactly: `2^53 - 1`.
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Converts a big-numberish JSON value to a BigInt.
+///
+/// # Arguments
+/// * `value` - A JSON integer within `±MAX_SAFE_INTEGER`, a decimal string or a `0x` hex string.
+///
+/// # Returns
+/// The integer, or an error for other values. Larger integers must be passed as strings,
+/// since JSON numbers beyond the safe range may have been rounded.
+pub fn to_bigint(value: &serde_json::Value) -> Result<BigInt, String> {
+    if let Some(n) = value.as_i64() {
+        if n.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(format!("{} is outside the safe integer range, pass it as a string", n));
+        }
+        return Ok(BigInt::from(n));
+    }
+    if let Some(n) = value.as_u64() {
+        return Err(format!("{} is outside the safe integer range, pass it as a string", n));
+    }
+    if value.is_number() {
+        return Err(format!("{} is not an integer", value));
+    }
+
+    let s = value.as_str().ok_or_else(|| format!("{} is not big-numberish", value))?;
+    parse_numeric(s)
+}
+
 pub fn is_type(value: &serde_json::Value, ty: SupportedType) -> bool {
     match ty {
         SupportedType::Number => is_number(value),
@@ -184,6 +228,18 @@ mod tests {
         assert!(is_hexadecimal(&json!("12"), false))
     }
 
+    #[test]
+    fn test_is_hexadecimal_rejects_empty_digits_and_bad_chars() {
+        assert!(!is_hexadecimal(&json!(""), false));
+        assert!(!is_hexadecimal(&json!(""), true));
+        assert!(!is_hexadecimal(&json!("0x"), true));
+        assert!(is_hexadecimal(&json!("0x0"), true));
+        assert!(is_hexadecimal(&json!("0X0"), true));
+        assert!(!is_hexadecimal(&json!("0xzz"), true));
+        assert!(!is_hexadecimal(&json!("12"), true));
+        assert!(!is_hexadecimal(&json!(12), false));
+    }
+
     #[test]
     fn test_is_big_number() {
         assert!(is_big_number(&json!("1")));
@@ -200,6 +256,20 @@ mod tests {
         assert!(!is_big_numberish(&json!("string")));
     }
 
+    #[test]
+    fn test_to_bigint_safe_integers() {
+        let two_pow_53 = 1u64 << 53;
+        assert!(to_bigint(&json!(two_pow_53)).is_err());
+        assert!(to_bigint(&json!(-(two_pow_53 as i64))).is_err());
+        assert_eq!(to_bigint(&json!(two_pow_53.to_string())).unwrap(), BigInt::from(two_pow_53));
+        assert_eq!(to_bigint(&json!(MAX_SAFE_INTEGER)).unwrap(), BigInt::from(MAX_SAFE_INTEGER));
+
+        assert_eq!(to_bigint(&json!("0x12")).unwrap(), BigInt::from(18));
+        assert!(to_bigint(&json!(1.5)).is_err());
+        assert!(to_bigint(&json!("string")).is_err());
+        assert!(to_bigint(&json!([1])).is_err());
+    }
+
     #[test]
     fn test_is_type_true() {
         assert!(is_type(&json!(1), SupportedType::Number));